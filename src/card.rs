@@ -1,16 +1,23 @@
 mod card_behaviors;
 mod card_constants;
+pub mod combat_events;
 
 use bevy::prelude::*;
 
-use card_behaviors::{cleanup_attackers_victim, Attacker, Health};
+use card_behaviors::cleanup_attackers_victim;
+use combat_events::CardSpawned;
 
-pub use card_behaviors::{CardDeath, Farmer, Nest, Quakka, SpawnedCard, Waterball};
-pub use card_constants::CardConsts;
+pub use card_behaviors::{
+    find_path, Attacker, CardDeath, Farmer, FollowPath, Health, Nest, Quakka, SpawnedCard,
+    SpriteAnimation, Waterball, WaterballTarget,
+};
+pub use card_constants::{CardConsts, CardConstsOverrides};
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
 use strum_macros::EnumIter;
 
+use crate::y_sort::YSort;
+
 #[derive(Clone, Copy, Debug, EnumIter, Serialize, Deserialize, PartialEq)]
 pub enum Card {
     Farmer,
@@ -55,12 +62,29 @@ impl Card {
         }
     }
 
+    /// Waterballs land from above and nests sit flat, so they don't sort like units
+    pub fn y_sort(&self) -> YSort {
+        match self {
+            Card::Waterball => YSort::FLYING,
+            Card::Nest => YSort::GROUND,
+            _ => YSort::default(),
+        }
+    }
+
     pub fn is_placeable_over_water(&self) -> bool {
         match self {
             Card::Waterball => true,
             _ => false,
         }
     }
+
+    /// Walls only stop what walks, cards that land from above can go on them
+    pub fn is_placeable_over_walls(&self) -> bool {
+        match self {
+            Card::Waterball => true,
+            _ => false,
+        }
+    }
 }
 
 #[derive(Component, Clone, Copy, Serialize, Deserialize, Deref, DerefMut)]
@@ -68,7 +92,8 @@ pub struct MaybeCard(pub Option<Card>);
 
 pub fn card(app: &mut App) {
     app.add_plugins(card_behaviors::card_behaviors)
-        .add_plugins(card_constants::card_constants);
+        .add_plugins(card_constants::card_constants)
+        .add_plugins(combat_events::combat_events_plugin);
 }
 
 pub struct SpawnCard {
@@ -84,22 +109,36 @@ impl SpawnCard {
 
 impl Command for SpawnCard {
     fn apply(self, world: &mut World) {
-        let asset_server = world.resource::<AssetServer>();
-        let card_consts = world.resource::<CardConsts>();
-
-        let mut spawned_card = match self.card {
-            Card::Farmer => world.spawn(farmer_bundle(self.position, asset_server, card_consts)),
-            Card::Quakka => world.spawn(quakka_bundle(self.position, asset_server, card_consts)),
-            Card::Waterball => {
-                world.spawn(waterball_bundle(self.position, asset_server, card_consts))
-            }
-            Card::Nest => world.spawn(nest_bundle(self.position, asset_server, card_consts)),
-        };
-
-        spawned_card.observe(cleanup_attackers_victim);
+        spawn_card(world, self.card, self.position);
     }
 }
 
+/// Same as [`SpawnCard`], for when the spawned entity is needed straight away
+pub fn spawn_card(world: &mut World, card: Card, position: Vec2) -> Entity {
+    let asset_server = world.resource::<AssetServer>();
+    let card_consts = world.resource::<CardConsts>();
+
+    let mut spawned_card = match card {
+        Card::Farmer => world.spawn(farmer_bundle(position, asset_server, card_consts)),
+        Card::Quakka => world.spawn(quakka_bundle(position, asset_server, card_consts)),
+        Card::Waterball => world.spawn(waterball_bundle(position, asset_server, card_consts)),
+        Card::Nest => world.spawn(nest_bundle(position, asset_server, card_consts)),
+    };
+
+    let entity = spawned_card
+        .insert(card.y_sort())
+        .observe(cleanup_attackers_victim)
+        .id();
+
+    world.send_event(CardSpawned {
+        entity,
+        card,
+        position,
+    });
+
+    entity
+}
+
 fn quakka_bundle(
     position: Vec2,
     asset_server: &AssetServer,
@@ -107,6 +146,7 @@ fn quakka_bundle(
 ) -> impl Bundle {
     (
         Card::Quakka.get_sprite(asset_server, card_consts),
+        SpriteAnimation::new(asset_server.load("animations/quakka.anim.json")),
         Transform {
             translation: position.extend(0.0),
             ..default()
@@ -133,6 +173,7 @@ fn farmer_bundle(
 ) -> impl Bundle {
     (
         Card::Farmer.get_sprite(asset_server, card_consts),
+        SpriteAnimation::new(asset_server.load("animations/farmer.anim.json")),
         Transform {
             translation: position.extend(0.),
             ..default()
@@ -1,8 +1,10 @@
 mod follow_path;
+mod sprite_animation;
 
 use crate::global::GameState;
 use crate::global::HEALTHBAR_SIZE;
-use crate::manage_level::IsPaused;
+use crate::manage_level::simulation_running;
+use crate::y_sort::CHILD_Z;
 use crate::{card::Card, manage_level::LevelEntity};
 use attacker::attacker_plugin;
 pub use attacker::cleanup_attackers_victim;
@@ -13,20 +15,23 @@ use bevy::prelude::*;
 use farmer::farmer_plugin;
 use farmer::kill_farmer_reaching_exit;
 use follow_path::follow_path_plugin;
+pub use follow_path::{find_path, FollowPath};
 use nest::nest_plugin;
 use quakka::quakka_plugin;
 pub use quakka::Quakka;
+pub use sprite_animation::SpriteAnimation;
+use sprite_animation::{sprite_animation_plugin, AnimClip};
 use std::time::Duration;
 use walk_animation::walk_animation_plugin;
-use walk_animation::WalkAnim;
 
+use super::combat_events::{DamageDealt, DamageSource, WaterballExploded};
 use super::CardConsts;
 
 #[derive(Component, DerefMut, Deref)]
 pub struct SpawnedCard(Card);
 
 #[derive(Component)]
-#[require(LevelEntity, WalkAnim)]
+#[require(LevelEntity)]
 #[require(SpawnedCard(Card::Farmer))]
 pub struct Farmer;
 
@@ -73,7 +78,7 @@ pub fn card_behaviors(app: &mut App) {
     app.add_systems(
         FixedUpdate,
         (
-            (kill_farmer_reaching_exit,).run_if(in_state(IsPaused::False)),
+            (kill_farmer_reaching_exit,).run_if(simulation_running),
             explode_waterballs,
             tick_waterball_timers,
             update_healthbars,
@@ -87,6 +92,7 @@ pub fn card_behaviors(app: &mut App) {
     .add_plugins(attacker_plugin)
     .add_plugins(quakka_plugin)
     .add_plugins(walk_animation_plugin)
+    .add_plugins(sprite_animation_plugin)
     .add_plugins(follow_path_plugin);
 }
 
@@ -103,7 +109,7 @@ fn initialize_healthbar(mut world: DeferredWorld, context: HookContext) {
 
     let healthbar = commands
         .spawn((
-            Transform::from_xyz(0., healthbar_height, 1.),
+            Transform::from_xyz(0., healthbar_height, CHILD_Z),
             HealthBar,
             Sprite {
                 rect: Some(Rect::from_corners(Vec2::ZERO, HEALTHBAR_SIZE.into())),
@@ -147,8 +153,11 @@ fn explode_waterballs(
     waterballs: Query<(Entity, &Waterball)>,
     mut health_q: Query<&mut Health>,
     transform_q: Query<&Transform>,
+    spawned_card_q: Query<&SpawnedCard>,
 
     mut commands: Commands,
+    mut damage_dealt_ev: EventWriter<DamageDealt>,
+    mut waterball_exploded_ev: EventWriter<WaterballExploded>,
 
     card_consts: Res<CardConsts>,
 ) {
@@ -157,6 +166,8 @@ fn explode_waterballs(
             continue;
         }
 
+        let mut hit_count = 0;
+
         for target in &mut waterball_targets {
             let target_transform = transform_q.get(target);
 
@@ -175,10 +186,31 @@ fn explode_waterballs(
 
             let target_health = health_q.get_mut(target);
             if let Ok(mut target_health) = target_health {
+                let was_alive = target_health.current_health > 0.;
                 target_health.current_health -= card_consts.waterball.damage;
+                hit_count += 1;
+
+                damage_dealt_ev.write(DamageDealt {
+                    attacker: waterball_e,
+                    attacker_card: Card::Waterball,
+                    victim: target,
+                    victim_card: **spawned_card_q.get(target).unwrap(),
+                    victim_position: transform_q
+                        .get(target)
+                        .map_or(Vec2::ZERO, |transform| transform.translation.truncate()),
+                    amount: card_consts.waterball.damage,
+                    source: DamageSource::Waterball,
+                    lethal: was_alive && target_health.current_health <= 0.,
+                });
             }
         }
 
+        waterball_exploded_ev.write(WaterballExploded {
+            waterball: waterball_e,
+            position: transform_q.get(waterball_e).unwrap().translation.truncate(),
+            hit_count,
+        });
+
         commands.entity(waterball_e).despawn();
     }
 }
@@ -202,14 +234,21 @@ mod attacker {
 
     use bevy::{color::palettes::css::RED, prelude::*};
 
-    use crate::{card::Card, global::GameState, manage_level::IsPaused};
+    use crate::{
+        card::{
+            combat_events::{AttackStarted, DamageDealt, DamageSource},
+            Card,
+        },
+        global::GameState,
+        manage_level::simulation_running,
+    };
 
-    use super::{Health, SpawnedCard};
+    use super::{AnimClip, Health, SpawnedCard, SpriteAnimation};
 
     pub fn attacker_plugin(app: &mut App) {
         app.add_systems(
             FixedUpdate,
-            attackers_attack.run_if(in_state(GameState::InGame).and(in_state(IsPaused::False))),
+            attackers_attack.run_if(in_state(GameState::InGame).and(simulation_running)),
         );
 
         if crate::debug::in_debug() {
@@ -260,6 +299,24 @@ mod attacker {
             self.cooldown.fraction()
         }
 
+        pub fn cooldown_elapsed(&self) -> Duration {
+            self.cooldown.elapsed()
+        }
+
+        /// Puts back state taken from [`Attacker::cooldown_elapsed`] and the current victim, for
+        /// rewinding
+        pub fn restore(
+            &mut self,
+            cooldown_elapsed: Duration,
+            current_victim: Option<(Entity, Option<f32>)>,
+        ) {
+            self.cooldown.set_elapsed(cooldown_elapsed);
+            self.current_victim = current_victim.map(|(entity, in_range_fraction)| CurrentVictim {
+                entity,
+                in_range_fraction,
+            });
+        }
+
         pub fn new(
             damage: f32,
             range: f32,
@@ -278,12 +335,20 @@ mod attacker {
 
     fn attackers_attack(
         mut possible_targets: Query<(Entity, &mut Health, &SpawnedCard), With<Transform>>,
-        attackers: Query<(Entity, &mut Attacker)>,
+        attackers: Query<(
+            Entity,
+            &mut Attacker,
+            &SpawnedCard,
+            Option<&mut SpriteAnimation>,
+        )>,
         transform_q: Query<&Transform>,
 
+        mut attack_started_ev: EventWriter<AttackStarted>,
+        mut damage_dealt_ev: EventWriter<DamageDealt>,
+
         time: Res<Time>,
     ) {
-        for (attacker_e, mut attacker) in attackers {
+        for (attacker_e, mut attacker, attacker_card, mut animation) in attackers {
             let attacker_translation = transform_q.get(attacker_e).unwrap().translation;
 
             let closest_target = possible_targets
@@ -311,13 +376,39 @@ mod attacker {
 
                 let in_attack_dist = dist_to_target < attacker.range;
                 if in_attack_dist {
+                    let was_attacking = attacker.current_victim_in_range()
+                        && attacker.current_victim() == Some(closest_target.0);
+                    if !was_attacking {
+                        attack_started_ev.write(AttackStarted {
+                            attacker: attacker_e,
+                            attacker_card: **attacker_card,
+                            victim: closest_target.0,
+                            victim_card: **closest_target.2,
+                        });
+                    }
+
                     attacker.cooldown.tick(time.delta());
 
                     current_victim.in_range_fraction = Some(dist_to_target / attacker.range);
 
                     if attacker.cooldown.finished() {
+                        let was_alive = closest_target.1.current_health > 0.;
                         closest_target.1.current_health -= attacker.damage;
                         attacker.cooldown.reset();
+                        if let Some(animation) = &mut animation {
+                            animation.play_once(AnimClip::Attack);
+                        }
+
+                        damage_dealt_ev.write(DamageDealt {
+                            attacker: attacker_e,
+                            attacker_card: **attacker_card,
+                            victim: closest_target.0,
+                            victim_card: **closest_target.2,
+                            victim_position: closest_target_translation.truncate(),
+                            amount: attacker.damage,
+                            source: DamageSource::Attack,
+                            lethal: was_alive && closest_target.1.current_health <= 0.,
+                        });
                     }
                 } else {
                     attacker.cooldown.reset();
@@ -427,7 +518,10 @@ mod walk_animation {
 }
 
 mod farmer {
-    use crate::{card::CardConsts, global::FARMER_EXIT_LOCATION};
+    use crate::{
+        card::{combat_events::FarmerEscaped, CardConsts},
+        global::FARMER_EXIT_LOCATION,
+    };
 
     use super::{follow_path::FollowPath, Farmer, Health};
     use bevy::prelude::*;
@@ -451,12 +545,16 @@ mod farmer {
     }
 
     pub fn kill_farmer_reaching_exit(
-        mut farmer_q: Query<(&mut Health, &Transform), With<Farmer>>,
+        mut farmer_q: Query<(Entity, &mut Health, &Transform), With<Farmer>>,
         exit: Single<&Transform, (With<Exit>, Without<Farmer>)>,
+        mut farmer_escaped_ev: EventWriter<FarmerEscaped>,
     ) {
-        for (mut farmer_health, farmer_transform) in farmer_q.iter_mut() {
-            if farmer_transform.translation.distance(exit.translation) < 1.0 {
+        for (farmer_e, mut farmer_health, farmer_transform) in farmer_q.iter_mut() {
+            if farmer_transform.translation.distance(exit.translation) < 1.0
+                && farmer_health.current_health > 0.0
+            {
                 farmer_health.current_health = 0.0;
+                farmer_escaped_ev.write(FarmerEscaped { farmer: farmer_e });
             };
         }
     }
@@ -464,6 +562,7 @@ mod farmer {
 
 mod nest {
     use super::{Attacker, Nest};
+    use crate::y_sort::YSort;
     use bevy::prelude::*;
 
     #[derive(Component)]
@@ -484,6 +583,7 @@ mod nest {
             const IMAGE_SIZE: Vec2 = Vec2::new(50.0, 65.0);
             commands.spawn((
                 Egg { from_nest: nest },
+                YSort::FLYING,
                 Sprite {
                     image: asset_server.load("nest-egg.png"),
                     custom_size: Some(IMAGE_SIZE * 0.5),
@@ -538,14 +638,12 @@ mod quakka {
     use crate::{
         card::{Card, CardConsts},
         global::GameState,
-        manage_level::{IsPaused, LevelEntity},
+        manage_level::{simulation_running, LevelEntity},
     };
     use bevy::prelude::*;
 
     use super::{
-        follow_path::FollowPath,
-        walk_animation::{CancelWalkAnim, WalkAnim},
-        Attacker, SpawnedCard, WaterballTarget,
+        follow_path::FollowPath, AnimClip, Attacker, SpawnedCard, SpriteAnimation, WaterballTarget,
     };
 
     #[derive(Component)]
@@ -556,12 +654,21 @@ mod quakka {
     pub fn quakka_plugin(app: &mut App) {
         app.add_systems(
             FixedUpdate,
-            chase_current_victim.run_if(in_state(GameState::InGame).and(in_state(IsPaused::False))),
+            chase_current_victim.run_if(in_state(GameState::InGame).and(simulation_running)),
         );
     }
 
     fn chase_current_victim(
-        quakkas: Query<(Entity, &Attacker, Option<&FollowPath>, &mut Transform), With<Quakka>>,
+        quakkas: Query<
+            (
+                Entity,
+                &Attacker,
+                Option<&FollowPath>,
+                &mut Transform,
+                &mut SpriteAnimation,
+            ),
+            With<Quakka>,
+        >,
         transform_q: Query<&Transform, Without<Quakka>>,
         spawned_card_q: Query<&SpawnedCard>,
         mut commands: Commands,
@@ -571,7 +678,7 @@ mod quakka {
     ) {
         const REGENERATE_PATH_TOLERANCE: f32 = 30.0;
 
-        for (quakka_e, attacker, follow_path, mut quakka_transform) in quakkas {
+        for (quakka_e, attacker, follow_path, mut quakka_transform, mut animation) in quakkas {
             if let Some(current_victim) = attacker.current_victim() {
                 let current_victim_translation =
                     transform_q.get(current_victim).unwrap().translation;
@@ -581,7 +688,7 @@ mod quakka {
                     commands.entity(quakka_e).try_remove::<FollowPath>();
 
                     if victim_card == Card::Farmer {
-                        commands.entity(quakka_e).insert_if_new(WalkAnim::default());
+                        animation.set_base(AnimClip::Walk);
 
                         let to = (current_victim_translation - quakka_transform.translation)
                             .normalize_or_zero();
@@ -590,7 +697,7 @@ mod quakka {
                             * (card_consts.farmer.speed * range_fraction + 0.2)
                             * time.delta_secs();
                     } else {
-                        commands.entity(quakka_e).insert(CancelWalkAnim);
+                        animation.set_base(AnimClip::Idle);
                     }
                 } else {
                     let new_path_bundle = || {
@@ -604,12 +711,12 @@ mod quakka {
                     };
 
                     if follow_path.is_none() {
-                        commands.entity(quakka_e).insert_if_new(WalkAnim::default());
+                        animation.set_base(AnimClip::Walk);
                         commands.entity(quakka_e).insert(new_path_bundle());
                     }
 
                     if let Some(follow_path) = follow_path {
-                        commands.entity(quakka_e).insert_if_new(WalkAnim::default());
+                        animation.set_base(AnimClip::Walk);
                         let goal_dist_to_victim =
                             current_victim_translation.truncate().distance(Vec2::new(
                                 follow_path.get_goal().0 as f32,
@@ -622,7 +729,7 @@ mod quakka {
                     }
                 }
             } else {
-                commands.entity(quakka_e).insert_if_new(CancelWalkAnim);
+                animation.set_base(AnimClip::Idle);
                 commands.entity(quakka_e).try_remove::<FollowPath>();
             }
         }
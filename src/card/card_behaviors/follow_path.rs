@@ -6,14 +6,15 @@ use bevy::{
 use pathfinding::prelude::astar;
 
 use crate::{
+    card::combat_events::{PathFinished, PathStarted},
     debug::in_debug,
-    global::{
-        get_entire_map_rect, get_left_river_rect, get_middle_river_rect, get_right_river_rect,
-        GameState,
-    },
-    manage_level::IsPaused,
+    global::{get_left_river_rect, get_middle_river_rect, get_right_river_rect, GameState},
+    manage_level::simulation_running,
+    terrain::Terrain,
 };
 
+use super::{sprite_animation::AnimClip, SpriteAnimation};
+
 #[derive(Component)]
 #[require(Transform)]
 #[component(on_insert = generate_path)]
@@ -22,12 +23,20 @@ pub struct FollowPath {
     path: Vec<Vec2>,
     current: usize,
     speed: f32,
+    finished: bool,
+    /// The terrain cut the goal off, so it's staying put until that changes
+    cut_off: bool,
 }
 
 pub fn follow_path_plugin(app: &mut App) {
     app.add_systems(
         FixedUpdate,
-        follow_paths.run_if(in_state(GameState::InGame).and(in_state(IsPaused::False))),
+        (
+            reroute_paths.run_if(resource_changed::<Terrain>),
+            follow_paths.run_if(simulation_running),
+        )
+            .chain()
+            .run_if(in_state(GameState::InGame)),
     );
 
     if in_debug() {
@@ -42,28 +51,78 @@ impl FollowPath {
             speed,
             path: Vec::default(),
             current: usize::default(),
+            finished: false,
+            cut_off: false,
         }
     }
 
     pub fn get_goal(&self) -> (i32, i32) {
         self.goal
     }
+
+    pub fn get_speed(&self) -> f32 {
+        self.speed
+    }
+
+    /// Follows `found_path` from its start, or stays at `start` if there isn't one
+    fn set_path(&mut self, start: Vec2, found_path: Option<Vec<Vec2>>) {
+        self.current = 0;
+        self.finished = false;
+        self.cut_off = false;
+
+        match found_path {
+            Some(found_path) => self.path = found_path,
+            None => {
+                // Walking straight there would go through walls, so it stays put instead
+                warn!(
+                    "No path from {start} to {:?}, the terrain cuts it off",
+                    self.goal
+                );
+                self.path = vec![start];
+                self.finished = true;
+                self.cut_off = true;
+            }
+        }
+    }
 }
 
-pub fn follow_paths(path_followers: Query<(&mut Transform, &mut FollowPath)>, time: Res<Time>) {
-    for (mut transform, mut follow_path) in path_followers {
+pub fn follow_paths(
+    path_followers: Query<(
+        Entity,
+        &mut Transform,
+        &mut FollowPath,
+        Option<&mut SpriteAnimation>,
+    )>,
+    mut path_finished_ev: EventWriter<PathFinished>,
+    time: Res<Time>,
+) {
+    for (e, mut transform, mut follow_path, animation) in path_followers {
         const TOLERANCE: f32 = 1.0;
+        let mut walking = true;
         let stop = follow_path.path[follow_path.current];
+        let is_last_stop = follow_path.current == follow_path.path.len() - 1;
 
-        if stop.distance(transform.translation.truncate()) < TOLERANCE
-            && follow_path.current < follow_path.path.len() - 1
-        {
+        if stop.distance(transform.translation.truncate()) < TOLERANCE && !is_last_stop {
             follow_path.current += 1;
+        } else if stop.distance(transform.translation.truncate()) < TOLERANCE {
+            walking = false;
+            if !follow_path.finished {
+                follow_path.finished = true;
+                path_finished_ev.write(PathFinished { entity: e });
+            }
         } else if stop.distance(transform.translation.truncate()) >= TOLERANCE {
             let mut to = stop - transform.translation.truncate();
             to = to.normalize_or_zero();
             transform.translation += (to * follow_path.speed * time.delta_secs()).extend(0.0);
         }
+
+        if let Some(mut animation) = animation {
+            animation.set_base(if walking {
+                AnimClip::Walk
+            } else {
+                AnimClip::Idle
+            });
+        }
     }
 }
 
@@ -78,7 +137,7 @@ impl Pos {
         ((a * a + b * b) as f32).sqrt() as u32
     }
 
-    fn successors(&self) -> Vec<(Pos, u32)> {
+    fn successors(&self, terrain: &Terrain) -> Vec<(Pos, u32)> {
         let straight_cost: u32 = 100;
 
         let &Pos(x, y) = self;
@@ -89,7 +148,7 @@ impl Pos {
             (Pos(x, y - ASTAR_RESOLUTION), straight_cost),
         ]
         .into_iter()
-        .filter(|(p, _)| reachable(p))
+        .filter(|(p, _)| reachable(p, terrain))
         .collect()
     }
 }
@@ -106,34 +165,51 @@ impl From<(i32, i32)> for Pos {
     }
 }
 
+/// The path from `start` to within [`ASTAR_RESOLUTION`] of `goal` around the terrain, or `None`
+/// if the terrain cuts the goal off
+pub fn find_path(start: Vec2, goal: (i32, i32), terrain: &Terrain) -> Option<Vec<Vec2>> {
+    let (found_path, _) = astar(
+        &Pos(start.x as i32, start.y as i32),
+        |p| p.successors(terrain),
+        |p| p.distance(&goal.into()) / 3,
+        |p| p.distance(&goal.into()) <= ASTAR_RESOLUTION.try_into().unwrap(),
+    )?;
+
+    Some(found_path.into_iter().map(Into::into).collect())
+}
+
 fn generate_path(mut world: DeferredWorld, context: HookContext) {
     let start = world
         .get::<Transform>(context.entity)
         .unwrap()
         .translation
-        .clone();
+        .truncate();
 
-    let mut follow_path = world.get_mut::<FollowPath>(context.entity).unwrap();
+    let goal = world.get::<FollowPath>(context.entity).unwrap().goal;
+    let found_path = find_path(start, goal, world.resource::<Terrain>());
 
-    let goal = follow_path.goal;
+    let mut follow_path = world.get_mut::<FollowPath>(context.entity).unwrap();
+    debug_assert!(follow_path.path.is_empty());
+    follow_path.set_path(start, found_path);
 
-    let found_path = astar(
-        &Pos(start.x as i32, start.y as i32),
-        |p| p.successors(),
-        |p| p.distance(&goal.into()) / 3,
-        |p| p.distance(&goal.into()) <= ASTAR_RESOLUTION.try_into().unwrap(),
-    );
+    world.send_event(PathStarted {
+        entity: context.entity,
+        goal,
+    });
+}
 
-    if let Some((found_path, _)) = found_path {
-        debug_assert!(follow_path.path.is_empty());
-        for pos in found_path {
-            follow_path.path.push(pos.into());
+/// Paths are only found when they're started, so painting over one afterwards would leave it
+/// walking through the new terrain
+fn reroute_paths(path_followers: Query<(&Transform, &mut FollowPath)>, terrain: Res<Terrain>) {
+    for (transform, mut follow_path) in path_followers {
+        // Nothing in the way matters any more once it's at the goal
+        if follow_path.finished && !follow_path.cut_off {
+            continue;
         }
-    } else {
-        // If it can't find a path, just go straight to the goal
-        follow_path
-            .path
-            .push(Vec2::new(goal.0 as f32, goal.1 as f32));
+
+        let start = transform.translation.truncate();
+        let found_path = find_path(start, follow_path.goal, &terrain);
+        follow_path.set_path(start, found_path);
     }
 }
 
@@ -145,13 +221,8 @@ fn display_paths(paths: Query<&FollowPath>, mut draw: Gizmos) {
     }
 }
 
-fn reachable(pos: &Pos) -> bool {
-    let pos: Vec2 = Vec2::new(pos.0 as f32, pos.1 as f32);
-
-    get_entire_map_rect().contains(pos)
-        && !get_left_river_rect().contains(pos)
-        && !get_middle_river_rect().contains(pos)
-        && !get_right_river_rect().contains(pos)
+fn reachable(pos: &Pos, terrain: &Terrain) -> bool {
+    terrain.is_walkable(Vec2::new(pos.0 as f32, pos.1 as f32))
 }
 
 fn debug_draw_river_boundaries(mut draw: Gizmos) {
@@ -167,3 +238,76 @@ fn debug_draw_river_boundaries(mut draw: Gizmos) {
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terrain::{TerrainKind, TERRAIN_CELL_SIZE};
+    use bevy::ecs::system::RunSystemOnce;
+
+    fn crosses_wall(follow_path: &FollowPath, terrain: &Terrain) -> bool {
+        follow_path
+            .path
+            .iter()
+            .any(|point| terrain.get(*point) == Some(TerrainKind::Wall))
+    }
+
+    #[test]
+    fn painting_a_wall_across_a_path_reroutes_it() {
+        let mut world = World::new();
+        world.init_resource::<Terrain>();
+        world.init_resource::<Events<PathStarted>>();
+
+        let farmer = world
+            .spawn((
+                Transform::from_xyz(0., 200., 0.),
+                FollowPath::new((0, 400), 10.),
+            ))
+            .id();
+
+        let mut terrain = Terrain::default();
+        for x in -2..=2 {
+            let pos = Vec2::new(x as f32 * TERRAIN_CELL_SIZE, 310.);
+            terrain.paint(pos, Some(TerrainKind::Wall));
+        }
+        assert!(crosses_wall(
+            world.get::<FollowPath>(farmer).unwrap(),
+            &terrain
+        ));
+
+        world.insert_resource(terrain.clone());
+        world.run_system_once(reroute_paths).unwrap();
+
+        let follow_path = world.get::<FollowPath>(farmer).unwrap();
+        assert!(!follow_path.cut_off);
+        assert_eq!(follow_path.current, 0);
+        assert!(!crosses_wall(follow_path, &terrain));
+    }
+
+    #[test]
+    fn clearing_the_terrain_in_the_way_frees_a_cut_off_path() {
+        let mut world = World::new();
+        let mut terrain = Terrain::default();
+        for x in -30..30 {
+            let pos = Vec2::new(x as f32 * TERRAIN_CELL_SIZE, 310.);
+            terrain.paint(pos, Some(TerrainKind::Wall));
+        }
+        world.insert_resource(terrain);
+        world.init_resource::<Events<PathStarted>>();
+
+        let farmer = world
+            .spawn((
+                Transform::from_xyz(0., 200., 0.),
+                FollowPath::new((0, 400), 10.),
+            ))
+            .id();
+        assert!(world.get::<FollowPath>(farmer).unwrap().cut_off);
+
+        world.insert_resource(Terrain::default());
+        world.run_system_once(reroute_paths).unwrap();
+
+        let follow_path = world.get::<FollowPath>(farmer).unwrap();
+        assert!(!follow_path.cut_off);
+        assert!(!follow_path.finished);
+    }
+}
@@ -0,0 +1,361 @@
+use bevy::prelude::*;
+use bevy_common_assets::json::JsonAssetPlugin;
+use serde::Deserialize;
+use std::collections::HashMap;
+use DuckSlayer::delete_all;
+
+use crate::{
+    card::combat_events::DamageDealt,
+    global::GameState,
+    hit_feedback::{flash_hit_sprites, HitFlash},
+    manage_level::{IsPaused, LevelEntity},
+};
+
+use super::walk_animation::{CancelWalkAnim, WalkAnim};
+
+/// Every clip a unit can have. Gameplay picks which one plays, the animation file decides what
+/// each one looks like
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum AnimClip {
+    Idle,
+    Walk,
+    Attack,
+    Hurt,
+    Die,
+}
+
+/// How a clip is drawn
+#[derive(Deserialize, Clone, Debug)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum ClipDef {
+    /// Frames from the sprite sheet, by index into the grid
+    Frames { frames: Vec<usize>, frame_secs: f32 },
+    /// Rocks the whole sprite side to side, see [`WalkAnim`]
+    Rock,
+    /// Leaves the sprite as it is
+    Still,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+struct SpriteSheet {
+    image: String,
+    frame_size: UVec2,
+    columns: u32,
+    rows: u32,
+}
+
+/// Loaded from `animations/*.anim.json`. Clips left out of the file are drawn as
+/// [`ClipDef::Still`]
+#[derive(Asset, TypePath, Deserialize, Debug)]
+pub struct AnimationSet {
+    /// Only needed by [`ClipDef::Frames`] clips
+    #[serde(default)]
+    sheet: Option<SpriteSheet>,
+    clips: HashMap<AnimClip, ClipDef>,
+}
+
+/// Plays clips from an [`AnimationSet`]. `base` loops until it's changed, a one shot clip plays
+/// over it once and then goes back to it
+#[derive(Component)]
+pub struct SpriteAnimation {
+    set: Handle<AnimationSet>,
+    base: AnimClip,
+    one_shot: Option<AnimClip>,
+    frame: usize,
+    elapsed: f32,
+}
+
+impl SpriteAnimation {
+    pub fn new(set: Handle<AnimationSet>) -> Self {
+        SpriteAnimation {
+            set,
+            base: AnimClip::Idle,
+            one_shot: None,
+            frame: 0,
+            elapsed: 0.,
+        }
+    }
+
+    pub fn set_base(&mut self, clip: AnimClip) {
+        if self.base == clip {
+            return;
+        }
+
+        self.base = clip;
+        if self.one_shot.is_none() {
+            self.restart();
+        }
+    }
+
+    pub fn play_once(&mut self, clip: AnimClip) {
+        self.one_shot = Some(clip);
+        self.restart();
+    }
+
+    fn current(&self) -> AnimClip {
+        self.one_shot.unwrap_or(self.base)
+    }
+
+    fn restart(&mut self) {
+        self.frame = 0;
+        self.elapsed = 0.;
+    }
+
+    /// Goes back to the base clip. Returns false if there was no one shot clip playing
+    fn end_one_shot(&mut self) -> bool {
+        let ended = self.one_shot.take().is_some();
+        if ended {
+            self.restart();
+        }
+        ended
+    }
+}
+
+/// What's left of a unit after it dies, here just long enough to play its die clip. It's a level
+/// entity so restarting or replacing the level clears it away with the units
+#[derive(Component)]
+#[require(Name::new("Corpse"), LevelEntity)]
+struct Corpse;
+
+pub fn sprite_animation_plugin(app: &mut App) {
+    app.add_plugins(JsonAssetPlugin::<AnimationSet>::new(&["anim.json"]))
+        .add_systems(Update, animate_sprites.run_if(in_state(IsPaused::False)))
+        .add_systems(
+            FixedPostUpdate,
+            // The flash tints the sprite straight away, the corpse has to copy it before that
+            play_hit_clips
+                .before(flash_hit_sprites)
+                .run_if(in_state(GameState::InGame)),
+        )
+        .add_systems(OnExit(GameState::InGame), delete_all::<Corpse>);
+}
+
+fn animate_sprites(
+    animated: Query<(
+        Entity,
+        &mut SpriteAnimation,
+        &mut Sprite,
+        Has<WalkAnim>,
+        Has<Corpse>,
+    )>,
+    animation_sets: Res<Assets<AnimationSet>>,
+    mut atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut layout_cache: Local<HashMap<AssetId<AnimationSet>, Handle<TextureAtlasLayout>>>,
+    asset_server: Res<AssetServer>,
+    time: Res<Time>,
+    mut commands: Commands,
+) {
+    for (e, mut animation, mut sprite, is_walking, is_corpse) in animated {
+        let Some(set) = animation_sets.get(&animation.set) else {
+            continue;
+        };
+
+        let clip = set
+            .clips
+            .get(&animation.current())
+            .cloned()
+            .unwrap_or(ClipDef::Still);
+
+        if !matches!(clip, ClipDef::Rock) && is_walking {
+            commands.entity(e).insert_if_new(CancelWalkAnim);
+        }
+
+        let one_shot_ended = match clip {
+            ClipDef::Frames { frames, frame_secs } => {
+                let Some(sheet) = &set.sheet else {
+                    continue;
+                };
+
+                animation.elapsed += time.delta_secs();
+                while frame_secs > 0. && animation.elapsed >= frame_secs {
+                    animation.elapsed -= frame_secs;
+                    animation.frame += 1;
+                }
+
+                let ended = animation.frame >= frames.len() && animation.end_one_shot();
+                if !ended && !frames.is_empty() {
+                    animation.frame %= frames.len();
+
+                    let layout = layout_cache
+                        .entry(animation.set.id())
+                        .or_insert_with(|| {
+                            atlas_layouts.add(TextureAtlasLayout::from_grid(
+                                sheet.frame_size,
+                                sheet.columns,
+                                sheet.rows,
+                                None,
+                                None,
+                            ))
+                        })
+                        .clone();
+
+                    if sprite.texture_atlas.is_none() {
+                        sprite.image = asset_server.load(&sheet.image);
+                    }
+                    sprite.texture_atlas = Some(TextureAtlas {
+                        layout,
+                        index: frames[animation.frame],
+                    });
+                }
+
+                ended
+            }
+            ClipDef::Rock => {
+                commands.entity(e).insert_if_new(WalkAnim::default());
+                animation.end_one_shot()
+            }
+            // Nothing to play, so a one shot is over straight away
+            ClipDef::Still => animation.end_one_shot(),
+        };
+
+        if one_shot_ended && is_corpse {
+            commands.entity(e).despawn();
+        }
+    }
+}
+
+fn play_hit_clips(
+    mut damage_dealt_evr: EventReader<DamageDealt>,
+    mut victims: Query<
+        (&mut SpriteAnimation, &Sprite, Option<&HitFlash>, &Transform),
+        Without<Corpse>,
+    >,
+    animation_sets: Res<Assets<AnimationSet>>,
+    mut commands: Commands,
+) {
+    for damage_dealt in damage_dealt_evr.read() {
+        let Ok((mut animation, sprite, hit_flash, transform)) =
+            victims.get_mut(damage_dealt.victim)
+        else {
+            continue;
+        };
+
+        if !damage_dealt.lethal {
+            animation.play_once(AnimClip::Hurt);
+            continue;
+        }
+
+        // The victim is despawned next tick, so the die clip is played by a stand in
+        let has_die_clip = animation_sets
+            .get(&animation.set)
+            .is_some_and(|set| set.clips.contains_key(&AnimClip::Die));
+        if has_die_clip {
+            let mut corpse_animation = SpriteAnimation::new(animation.set.clone());
+            corpse_animation.play_once(AnimClip::Die);
+
+            // A flash from an earlier hit would otherwise stay on the corpse for its whole clip
+            let mut corpse_sprite = sprite.clone();
+            if let Some(hit_flash) = hit_flash {
+                corpse_sprite.color = hit_flash.original_color();
+            }
+
+            commands.spawn((Corpse, corpse_animation, corpse_sprite, *transform));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::{asset::AssetPlugin, ecs::system::RunSystemOnce};
+    use std::time::Duration;
+
+    #[test]
+    fn one_shot_plays_over_base_then_returns_to_it() {
+        let mut animation = SpriteAnimation::new(Handle::default());
+        animation.set_base(AnimClip::Walk);
+        animation.play_once(AnimClip::Attack);
+        assert_eq!(animation.current(), AnimClip::Attack);
+
+        assert!(animation.end_one_shot());
+        assert_eq!(animation.current(), AnimClip::Walk);
+        assert!(!animation.end_one_shot());
+    }
+
+    #[test]
+    fn changing_base_during_one_shot_keeps_its_frame() {
+        let mut animation = SpriteAnimation::new(Handle::default());
+        animation.play_once(AnimClip::Hurt);
+        animation.frame = 2;
+
+        animation.set_base(AnimClip::Walk);
+        assert_eq!(animation.current(), AnimClip::Hurt);
+        assert_eq!(animation.frame, 2);
+
+        animation.end_one_shot();
+        assert_eq!(animation.current(), AnimClip::Walk);
+        assert_eq!(animation.frame, 0);
+    }
+
+    #[test]
+    fn setting_the_same_base_doesnt_restart_it() {
+        let mut animation = SpriteAnimation::new(Handle::default());
+        animation.set_base(AnimClip::Walk);
+        animation.frame = 3;
+
+        animation.set_base(AnimClip::Walk);
+        assert_eq!(animation.frame, 3);
+    }
+
+    #[test]
+    fn frames_clips_parse() {
+        let set: AnimationSet = serde_json::from_str(
+            r#"{
+                "sheet": { "image": "quakka-sheet.png", "frame_size": [64, 64], "columns": 4, "rows": 1 },
+                "clips": { "walk": { "kind": "frames", "frames": [0, 1, 2, 3], "frame_secs": 0.1 } }
+            }"#,
+        )
+        .unwrap();
+
+        assert!(set.sheet.is_some());
+        assert!(matches!(
+            set.clips.get(&AnimClip::Walk),
+            Some(ClipDef::Frames { frames, .. }) if frames.len() == 4
+        ));
+    }
+
+    #[test]
+    fn frames_clip_steps_through_the_sheet_and_wraps() {
+        let mut app = App::new();
+        app.add_plugins((TaskPoolPlugin::default(), AssetPlugin::default()))
+            .init_asset::<AnimationSet>()
+            .init_asset::<TextureAtlasLayout>()
+            .init_asset::<Image>()
+            .init_resource::<Time>();
+
+        let set: AnimationSet = serde_json::from_str(
+            r#"{
+                "sheet": { "image": "quakka-sheet.png", "frame_size": [64, 64], "columns": 4, "rows": 2 },
+                "clips": { "walk": { "kind": "frames", "frames": [4, 5, 6], "frame_secs": 0.25 } }
+            }"#,
+        )
+        .unwrap();
+        let set = app
+            .world_mut()
+            .resource_mut::<Assets<AnimationSet>>()
+            .add(set);
+
+        let mut animation = SpriteAnimation::new(set);
+        animation.set_base(AnimClip::Walk);
+        let unit = app.world_mut().spawn((animation, Sprite::default())).id();
+
+        let mut tick = || {
+            let world = app.world_mut();
+            world
+                .resource_mut::<Time>()
+                .advance_by(Duration::from_millis(250));
+            world.run_system_once(animate_sprites).unwrap();
+            world
+                .get::<Sprite>(unit)
+                .unwrap()
+                .texture_atlas
+                .as_ref()
+                .map(|atlas| atlas.index)
+        };
+
+        assert_eq!(tick(), Some(5));
+        assert_eq!(tick(), Some(6));
+        assert_eq!(tick(), Some(4));
+    }
+}
@@ -9,6 +9,15 @@ pub struct CardConsts {
     pub nest: NestConsts,
 }
 
+impl CardConsts {
+    /// The defaults with `overrides` merged on top
+    pub fn from_overrides(overrides: &CardConstsOverrides) -> CardConsts {
+        let mut card_consts = CardConsts::default();
+        overrides.apply(&mut card_consts);
+        card_consts
+    }
+}
+
 pub fn card_constants(app: &mut App) {
     app.init_resource::<CardConsts>();
 }
@@ -96,3 +105,175 @@ impl Default for NestConsts {
         }
     }
 }
+
+/// Partial [`CardConsts`] stored in a level file. Only fields that differ from the defaults are
+/// written, and anything left out falls back to [`CardConsts::default`]
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct CardConstsOverrides {
+    #[serde(skip_serializing_if = "FarmerConstsOverrides::is_empty")]
+    pub farmer: FarmerConstsOverrides,
+    #[serde(skip_serializing_if = "QuakkaConstsOverrides::is_empty")]
+    pub quakka: QuakkaConstsOverrides,
+    #[serde(skip_serializing_if = "WaterballConstsOverrides::is_empty")]
+    pub waterball: WaterballConstsOverrides,
+    #[serde(skip_serializing_if = "NestConstsOverrides::is_empty")]
+    pub nest: NestConstsOverrides,
+}
+
+impl CardConstsOverrides {
+    pub fn is_empty(&self) -> bool {
+        *self == CardConstsOverrides::default()
+    }
+
+    /// Records every field of `current` that isn't the same as in `base`
+    pub fn from_diff(base: &CardConsts, current: &CardConsts) -> CardConstsOverrides {
+        CardConstsOverrides {
+            farmer: FarmerConstsOverrides {
+                size: diff(base.farmer.size, current.farmer.size),
+                speed: diff(base.farmer.speed, current.farmer.speed),
+            },
+            quakka: QuakkaConstsOverrides {
+                size: diff(base.quakka.size, current.quakka.size),
+                damage: diff(base.quakka.damage, current.quakka.damage),
+                hit_cooldown: diff(base.quakka.hit_cooldown, current.quakka.hit_cooldown),
+                range: diff(base.quakka.range, current.quakka.range),
+                speed: diff(base.quakka.speed, current.quakka.speed),
+            },
+            waterball: WaterballConstsOverrides {
+                radius: diff(base.waterball.radius, current.waterball.radius),
+                damage: diff(base.waterball.damage, current.waterball.damage),
+                explosion_delay: diff(
+                    base.waterball.explosion_delay,
+                    current.waterball.explosion_delay,
+                ),
+            },
+            nest: NestConstsOverrides {
+                size: diff(base.nest.size, current.nest.size),
+                range: diff(base.nest.range, current.nest.range),
+                damage: diff(base.nest.damage, current.nest.damage),
+            },
+        }
+    }
+
+    pub fn apply(&self, consts: &mut CardConsts) {
+        let FarmerConstsOverrides { size, speed } = &self.farmer;
+        overwrite(&mut consts.farmer.size, size);
+        overwrite(&mut consts.farmer.speed, speed);
+
+        let QuakkaConstsOverrides {
+            size,
+            damage,
+            hit_cooldown,
+            range,
+            speed,
+        } = &self.quakka;
+        overwrite(&mut consts.quakka.size, size);
+        overwrite(&mut consts.quakka.damage, damage);
+        overwrite(&mut consts.quakka.hit_cooldown, hit_cooldown);
+        overwrite(&mut consts.quakka.range, range);
+        overwrite(&mut consts.quakka.speed, speed);
+
+        let WaterballConstsOverrides {
+            radius,
+            damage,
+            explosion_delay,
+        } = &self.waterball;
+        overwrite(&mut consts.waterball.radius, radius);
+        overwrite(&mut consts.waterball.damage, damage);
+        overwrite(&mut consts.waterball.explosion_delay, explosion_delay);
+
+        let NestConstsOverrides {
+            size,
+            range,
+            damage,
+        } = &self.nest;
+        overwrite(&mut consts.nest.size, size);
+        overwrite(&mut consts.nest.range, range);
+        overwrite(&mut consts.nest.damage, damage);
+    }
+}
+
+fn diff<T: PartialEq>(base: T, current: T) -> Option<T> {
+    if base == current {
+        None
+    } else {
+        Some(current)
+    }
+}
+
+fn overwrite<T: Copy>(constant: &mut T, with: &Option<T>) {
+    if let Some(with) = with {
+        *constant = *with;
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct FarmerConstsOverrides {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<(f32, f32)>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub speed: Option<f32>,
+}
+
+impl FarmerConstsOverrides {
+    fn is_empty(&self) -> bool {
+        *self == FarmerConstsOverrides::default()
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct QuakkaConstsOverrides {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<(f32, f32)>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub damage: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hit_cooldown: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub range: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub speed: Option<f32>,
+}
+
+impl QuakkaConstsOverrides {
+    fn is_empty(&self) -> bool {
+        *self == QuakkaConstsOverrides::default()
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct WaterballConstsOverrides {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub radius: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub damage: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explosion_delay: Option<f32>,
+}
+
+impl WaterballConstsOverrides {
+    fn is_empty(&self) -> bool {
+        *self == WaterballConstsOverrides::default()
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct NestConstsOverrides {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<(f32, f32)>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub range: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub damage: Option<f32>,
+}
+
+impl NestConstsOverrides {
+    fn is_empty(&self) -> bool {
+        *self == NestConstsOverrides::default()
+    }
+}
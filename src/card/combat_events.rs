@@ -0,0 +1,291 @@
+use bevy::prelude::*;
+use serde::Serialize;
+
+use crate::{global::GameState, manage_level::simulation_running};
+
+use super::Card;
+
+#[derive(Event, Clone)]
+pub struct CardSpawned {
+    pub entity: Entity,
+    pub card: Card,
+    pub position: Vec2,
+}
+
+/// An attacker has a victim in range and starts winding up its first hit on them
+#[derive(Event, Clone)]
+pub struct AttackStarted {
+    pub attacker: Entity,
+    pub attacker_card: Card,
+    pub victim: Entity,
+    pub victim_card: Card,
+}
+
+#[derive(Clone, Copy, Debug, Serialize)]
+pub enum DamageSource {
+    Attack,
+    Waterball,
+}
+
+#[derive(Event, Clone)]
+pub struct DamageDealt {
+    pub attacker: Entity,
+    pub attacker_card: Card,
+    pub victim: Entity,
+    pub victim_card: Card,
+    /// Where the victim was when it was hit, it may be despawned by the time this is read
+    pub victim_position: Vec2,
+    pub amount: f32,
+    pub source: DamageSource,
+    /// This hit took the victim's health to 0
+    pub lethal: bool,
+}
+
+#[derive(Event, Clone)]
+pub struct WaterballExploded {
+    pub waterball: Entity,
+    pub position: Vec2,
+    pub hit_count: u32,
+}
+
+#[derive(Event, Clone)]
+pub struct PathStarted {
+    pub entity: Entity,
+    pub goal: (i32, i32),
+}
+
+#[derive(Event, Clone)]
+pub struct PathFinished {
+    pub entity: Entity,
+}
+
+#[derive(Event, Clone)]
+pub struct FarmerEscaped {
+    pub farmer: Entity,
+}
+
+/// One line of the combat log. Entities are written as their bits rather than their index, indices
+/// are reused once a card dies so they'd mix different cards up
+#[derive(Serialize)]
+#[serde(tag = "event")]
+enum CombatLogEvent {
+    CardSpawned {
+        entity: u64,
+        card: Card,
+        position: Vec2,
+    },
+    AttackStarted {
+        attacker: u64,
+        attacker_card: Card,
+        victim: u64,
+        victim_card: Card,
+    },
+    DamageDealt {
+        attacker: u64,
+        attacker_card: Card,
+        victim: u64,
+        victim_card: Card,
+        amount: f32,
+        source: DamageSource,
+        lethal: bool,
+    },
+    WaterballExploded {
+        waterball: u64,
+        position: Vec2,
+        hit_count: u32,
+    },
+    PathStarted {
+        entity: u64,
+        goal: (i32, i32),
+    },
+    PathFinished {
+        entity: u64,
+    },
+    FarmerEscaped {
+        farmer: u64,
+    },
+}
+
+trait LogEvent: Event {
+    fn log_event(&self) -> CombatLogEvent;
+}
+
+impl LogEvent for CardSpawned {
+    fn log_event(&self) -> CombatLogEvent {
+        CombatLogEvent::CardSpawned {
+            entity: self.entity.to_bits(),
+            card: self.card,
+            position: self.position,
+        }
+    }
+}
+
+impl LogEvent for AttackStarted {
+    fn log_event(&self) -> CombatLogEvent {
+        CombatLogEvent::AttackStarted {
+            attacker: self.attacker.to_bits(),
+            attacker_card: self.attacker_card,
+            victim: self.victim.to_bits(),
+            victim_card: self.victim_card,
+        }
+    }
+}
+
+impl LogEvent for DamageDealt {
+    fn log_event(&self) -> CombatLogEvent {
+        CombatLogEvent::DamageDealt {
+            attacker: self.attacker.to_bits(),
+            attacker_card: self.attacker_card,
+            victim: self.victim.to_bits(),
+            victim_card: self.victim_card,
+            amount: self.amount,
+            source: self.source,
+            lethal: self.lethal,
+        }
+    }
+}
+
+impl LogEvent for WaterballExploded {
+    fn log_event(&self) -> CombatLogEvent {
+        CombatLogEvent::WaterballExploded {
+            waterball: self.waterball.to_bits(),
+            position: self.position,
+            hit_count: self.hit_count,
+        }
+    }
+}
+
+impl LogEvent for PathStarted {
+    fn log_event(&self) -> CombatLogEvent {
+        CombatLogEvent::PathStarted {
+            entity: self.entity.to_bits(),
+            goal: self.goal,
+        }
+    }
+}
+
+impl LogEvent for PathFinished {
+    fn log_event(&self) -> CombatLogEvent {
+        CombatLogEvent::PathFinished {
+            entity: self.entity.to_bits(),
+        }
+    }
+}
+
+impl LogEvent for FarmerEscaped {
+    fn log_event(&self) -> CombatLogEvent {
+        CombatLogEvent::FarmerEscaped {
+            farmer: self.farmer.to_bits(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct CombatLogEntry {
+    /// Seconds of unpaused game time since the log was started
+    time: f32,
+    #[serde(flatten)]
+    event: CombatLogEvent,
+}
+
+/// Every combat event since the level was spawned, for writing out with [`CombatLog::to_json`]
+#[derive(Resource, Default)]
+pub struct CombatLog {
+    elapsed: f32,
+    entries: Vec<CombatLogEntry>,
+    /// Set while cards are being put back as they were, nothing is actually happening to them
+    skip_pending: bool,
+}
+
+/// How far the log had got, so it can be rewound along with the editor's timeline
+#[derive(Clone, Copy)]
+pub struct CombatLogCheckpoint {
+    len: usize,
+    elapsed: f32,
+}
+
+impl CombatLog {
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn checkpoint(&self) -> CombatLogCheckpoint {
+        CombatLogCheckpoint {
+            len: self.entries.len(),
+            elapsed: self.elapsed,
+        }
+    }
+
+    /// Drops everything logged after `checkpoint`
+    pub fn rewind(&mut self, checkpoint: CombatLogCheckpoint) {
+        self.entries.truncate(checkpoint.len);
+        self.elapsed = checkpoint.elapsed;
+    }
+
+    /// Events sent before the log is next written to are left out of it
+    pub fn skip_pending_events(&mut self) {
+        self.skip_pending = true;
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&self.entries).unwrap()
+    }
+}
+
+pub fn combat_events_plugin(app: &mut App) {
+    app.add_event::<CardSpawned>()
+        .add_event::<AttackStarted>()
+        .add_event::<DamageDealt>()
+        .add_event::<WaterballExploded>()
+        .add_event::<PathStarted>()
+        .add_event::<PathFinished>()
+        .add_event::<FarmerEscaped>()
+        .add_systems(
+            FixedUpdate,
+            tick_combat_log_clock.run_if(in_state(GameState::InGame).and(simulation_running)),
+        )
+        .add_systems(
+            FixedPostUpdate,
+            (
+                record::<CardSpawned>,
+                record::<AttackStarted>,
+                record::<DamageDealt>,
+                record::<WaterballExploded>,
+                record::<PathStarted>,
+                record::<PathFinished>,
+                record::<FarmerEscaped>,
+                stop_skipping,
+            )
+                .chain()
+                .run_if(in_state(GameState::InGame)),
+        )
+        .init_resource::<CombatLog>();
+}
+
+/// Called whenever the level is spawned from scratch
+pub fn clear_combat_log(mut combat_log: ResMut<CombatLog>) {
+    *combat_log = CombatLog::default();
+}
+
+fn tick_combat_log_clock(mut combat_log: ResMut<CombatLog>, time: Res<Time>) {
+    combat_log.elapsed += time.delta_secs();
+}
+
+fn record<E: LogEvent>(mut events: EventReader<E>, mut combat_log: ResMut<CombatLog>) {
+    if combat_log.skip_pending {
+        events.clear();
+        return;
+    }
+
+    let time = combat_log.elapsed;
+    for event in events.read() {
+        combat_log.entries.push(CombatLogEntry {
+            time,
+            event: event.log_event(),
+        });
+    }
+}
+
+fn stop_skipping(mut combat_log: ResMut<CombatLog>) {
+    combat_log.skip_pending = false;
+}
@@ -0,0 +1,101 @@
+mod check;
+
+use bevy::prelude::*;
+use clap::{ArgGroup, Parser, Subcommand};
+use std::{path::PathBuf, sync::OnceLock};
+
+use crate::{
+    card::{CardConsts, CardConstsOverrides},
+    global::InEditorRes,
+    load_error::{read_json_file, ShowLoadError},
+    manage_level::{
+        read_level_file, EnterLevel, Level, LevelSource, WatchedConstsFile, WatchedFile,
+    },
+};
+
+pub use check::check_levels;
+
+#[derive(Parser, Debug)]
+#[command(version, about = "Slay ducks")]
+#[command(group(ArgGroup::new("launch").args(["level", "editor"])))]
+pub struct Cli {
+    /// Skip the menus and play this level
+    #[arg(long, value_name = "PATH")]
+    pub level: Option<PathBuf>,
+    /// Open the editor, with this level if one is given
+    #[arg(long, value_name = "PATH", num_args = 0..=1)]
+    pub editor: Option<Option<PathBuf>>,
+    /// Play with these constants instead of the level's own
+    #[arg(long, value_name = "PATH", requires = "launch")]
+    pub consts: Option<PathBuf>,
+    /// Show the debug overlays, the same as DUCKSLAYER_DEBUG=true
+    #[arg(long)]
+    pub debug: bool,
+    /// Override the window's scale factor, for screens where it's detected wrong
+    #[arg(long)]
+    pub scale_factor: Option<f32>,
+    #[command(subcommand)]
+    pub command: Option<CliCommand>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum CliCommand {
+    /// Check that level files load and can be won, without opening a window
+    Check {
+        #[arg(required = true, value_name = "PATH")]
+        levels: Vec<PathBuf>,
+    },
+}
+
+static CLI: OnceLock<Cli> = OnceLock::new();
+
+/// The arguments the game was started with, parsed on first use
+pub fn cli() -> &'static Cli {
+    CLI.get_or_init(Cli::parse)
+}
+
+pub fn cli_plugin(app: &mut App) {
+    app.add_systems(PostStartup, launch_from_cli);
+}
+
+fn launch_from_cli(world: &mut World) {
+    let cli = cli();
+
+    let level_path = match &cli.editor {
+        Some(level_path) => level_path.as_ref(),
+        None => cli.level.as_ref(),
+    };
+
+    if cli.editor.is_none() && level_path.is_none() {
+        return;
+    }
+
+    let mut level = match level_path {
+        Some(level_path) => match read_level_file(level_path) {
+            Ok(loaded) => loaded.level,
+            Err(err) => {
+                ShowLoadError(err).apply(world);
+                return;
+            }
+        },
+        None => Level::get_stub(),
+    };
+
+    if let Some(consts_path) = &cli.consts {
+        match read_json_file::<CardConsts>(consts_path) {
+            Ok(card_consts) => {
+                level.consts_overrides =
+                    CardConstsOverrides::from_diff(&CardConsts::default(), &card_consts);
+                world.insert_resource(WatchedConstsFile(WatchedFile::new(consts_path)));
+            }
+            Err(err) => ShowLoadError(err).apply(world),
+        }
+    }
+
+    **world.resource_mut::<InEditorRes>() = cli.editor.is_some();
+    EnterLevel(level).apply(world);
+
+    if let Some(level_path) = level_path {
+        world.insert_resource(LevelSource::File(WatchedFile::new(level_path)));
+    }
+}
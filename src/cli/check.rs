@@ -0,0 +1,142 @@
+use std::path::PathBuf;
+
+use crate::{
+    card::{find_path, Card},
+    global::{get_entire_map_rect, FARMER_EXIT_LOCATION},
+    manage_level::{read_level_file, Level},
+    terrain::Terrain,
+};
+
+/// Checks every level, printing what's wrong with each. Returns the exit code for the process, 1
+/// if any level has an error
+pub fn check_levels(levels: &[PathBuf]) -> i32 {
+    let mut failed = 0;
+
+    for path in levels {
+        let (errors, warnings) = match read_level_file(path) {
+            Ok(loaded) => {
+                let (errors, mut warnings) = check_level(&loaded.level);
+                if let Some(from_version) = loaded.migrated_from {
+                    warnings.push(format!(
+                        "written in level version {from_version}, re-save it in the editor to \
+                         upgrade it"
+                    ));
+                }
+                (errors, warnings)
+            }
+            Err(err) => (vec![err.to_string()], vec![]),
+        };
+
+        if errors.is_empty() && warnings.is_empty() {
+            println!("{}: ok", path.display());
+            continue;
+        }
+
+        println!("{}:", path.display());
+        for error in &errors {
+            println!("  error: {error}");
+        }
+        for warning in &warnings {
+            println!("  warning: {warning}");
+        }
+
+        if !errors.is_empty() {
+            failed += 1;
+        }
+    }
+
+    println!(
+        "{} of {} levels passed",
+        levels.len() - failed,
+        levels.len()
+    );
+
+    if failed == 0 {
+        0
+    } else {
+        1
+    }
+}
+
+/// Returns the errors and warnings for a level that loaded
+fn check_level(level: &Level) -> (Vec<String>, Vec<String>) {
+    let mut errors = Vec::new();
+    let mut warnings = Vec::new();
+
+    let map_rect = get_entire_map_rect();
+    let terrain = Terrain::from_tiles(&level.terrain);
+    for (card, position) in &level.cards {
+        let card_name = card.to_string();
+        if !map_rect.contains(*position) {
+            errors.push(format!("{card_name} at {position} is outside the arena"));
+        } else if !terrain.is_placeable(*card, *position) {
+            errors.push(format!(
+                "{card_name} at {position} is on terrain it can't be on"
+            ));
+        } else if *card == Card::Farmer
+            && find_path(*position, FARMER_EXIT_LOCATION, &terrain).is_none()
+        {
+            warnings.push(format!(
+                "{card_name} at {position} can't reach the exit, the terrain cuts it off so it'll \
+                 stand still"
+            ));
+        }
+    }
+
+    for zone in &level.placement_zones {
+        if zone.rect.intersect(map_rect).is_empty() {
+            warnings.push(format!(
+                "has a {} placement zone outside the arena, it can never be placed",
+                zone.card.to_string()
+            ));
+        }
+    }
+
+    // Cards can only die once, so there have to be enough of them between the arena and the deck
+    let available = |card| {
+        level.cards.iter().filter(|(c, _)| *c == card).count()
+            + level
+                .starting_deckbar
+                .iter()
+                .filter(|c| **c == card)
+                .count()
+    };
+
+    let win_card = level.win_condition.card;
+    if available(win_card) < level.win_condition.count_dead as usize {
+        errors.push(format!(
+            "can't be won, it needs {} {} to be eliminated but there are only {}",
+            level.win_condition.count_dead,
+            win_card.to_string(),
+            available(win_card)
+        ));
+    }
+
+    let lose_card = level.lose_condition.card;
+    if available(lose_card) < level.lose_condition.count_dead as usize {
+        warnings.push(format!(
+            "can't be lost, it needs {} {} deaths but there are only {}",
+            level.lose_condition.count_dead,
+            lose_card.to_string(),
+            available(lose_card)
+        ));
+    }
+
+    if win_card == lose_card {
+        warnings.push(format!(
+            "{} is both the card to eliminate and the card to protect",
+            win_card.to_string()
+        ));
+    }
+
+    if level
+        .metadata
+        .hints
+        .iter()
+        .any(|hint| hint.trim().is_empty())
+    {
+        warnings.push("has an empty hint".to_string());
+    }
+
+    (errors, warnings)
+}
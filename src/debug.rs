@@ -8,7 +8,9 @@ use bevy_inspector_egui::quick::WorldInspectorPlugin;
 use crate::global::CursorWorldCoords;
 
 pub fn in_debug() -> bool {
-    if let Ok(duckslayer_debug) = std::env::var("DUCKSLAYER_DEBUG") {
+    if crate::cli::cli().debug {
+        true
+    } else if let Ok(duckslayer_debug) = std::env::var("DUCKSLAYER_DEBUG") {
         duckslayer_debug == "true"
     } else {
         false
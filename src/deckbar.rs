@@ -1,7 +1,9 @@
 use crate::{
     card::{Card, CardConsts, MaybeCard},
     ingame_ui_root::InGameUiRoot,
+    placement::{Placement, INVALID_PLACEMENT_COLOR},
     volume_settings::VolumeSettings,
+    y_sort::YSort,
 };
 
 use bevy::{
@@ -10,6 +12,7 @@ use bevy::{
     ecs::{schedule::ScheduleConfigs, system::ScheduleSystem},
     prelude::*,
     render::texture::TRANSPARENT_IMAGE_HANDLE,
+    ui::RelativeCursorPosition,
 };
 
 use crate::global::*;
@@ -20,6 +23,16 @@ pub struct SelectedCard;
 #[derive(Component)]
 pub struct DeckBarRoot;
 
+/// Exists while the selected card is being dragged from the deckbar onto the arena
+#[derive(Resource)]
+pub struct DraggedCard {
+    /// Deckbar slot the card was dragged from, it stays pressed until the card is dropped
+    pub slot: Entity,
+    /// A click that wobbles a little is a drag too, this tells it apart from one that was dragged
+    /// out and back to cancel it
+    pub has_left_deckbar: bool,
+}
+
 #[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct InitializeDeckbar;
 
@@ -72,6 +85,7 @@ fn initialize_deckbar(mut commands: Commands, ingame_ui_root: Res<InGameUiRoot>)
             BorderColor(RED.into()),
             BackgroundColor(GREY.into()),
             Button, // So that it is taken into account for is_pointer_over_ui
+            RelativeCursorPosition::default(),
         ))
         .with_children(|p| {
             fn get_empty_card_node_bundle() -> impl Bundle {
@@ -207,6 +221,7 @@ fn spawn_hover_sprite(mut commands: Commands) {
     commands.spawn((
         HoverSprite,
         Transform::default(),
+        YSort::default(),
         Sprite {
             color: Color::NONE,
             ..default()
@@ -225,7 +240,7 @@ fn hover_sprite_when_card_selected(
 
     selected_card: Option<Single<&MaybeCard, With<SelectedCard>>>,
     asset_server: Res<AssetServer>,
-    cursor_world_coords: Res<CursorWorldCoords>,
+    placement: Placement,
 
     card_consts: Res<CardConsts>,
 ) {
@@ -242,17 +257,27 @@ fn hover_sprite_when_card_selected(
                 hide_hover_sprite();
             }
             Some(selected_card) => {
-                commands.entity(*hover_sprite).insert(Sprite {
-                    color: Color::linear_rgba(1., 1., 1., 0.5),
-                    ..selected_card.get_sprite(&asset_server, &card_consts)
-                });
+                let color = if placement.can_place(selected_card) {
+                    Color::WHITE
+                } else {
+                    INVALID_PLACEMENT_COLOR.into()
+                };
+
+                commands.entity(*hover_sprite).insert((
+                    Sprite {
+                        color: color.with_alpha(0.5),
+                        ..selected_card.get_sprite(&asset_server, &card_consts)
+                    },
+                    // Sorts the same as the card would once it's placed
+                    selected_card.y_sort(),
+                ));
             }
         }
     } else {
         hide_hover_sprite()
     }
 
-    let cursor_world_coords = cursor_world_coords.0;
+    let cursor_world_coords = placement.cursor();
     commands
         .entity(*hover_sprite)
         .entry::<Transform>()
@@ -1,5 +1,6 @@
 use bevy::{prelude::*, window::PrimaryWindow};
 use bevy_egui::input::EguiWantsInput;
+use std::path::PathBuf;
 
 use crate::asset_load_schedule::AssetLoad;
 
@@ -61,6 +62,11 @@ pub fn get_entire_map_rect() -> Rect {
     Rect::from_corners((-683., 480.).into(), (683., -384.).into()) // Slightly extended since exit is above the map
 }
 
+/// Where files that belong to the player rather than the game are kept, like editor autosaves
+pub fn user_data_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|data_dir| data_dir.join("DuckSlayer"))
+}
+
 pub fn global(app: &mut App) {
     app.add_systems(AssetLoad, load_images)
         .add_systems(
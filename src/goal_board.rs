@@ -1,7 +1,7 @@
-use bevy::prelude::*;
+use bevy::{color::palettes::tailwind::*, prelude::*};
 
 use crate::{
-    card::Card,
+    card::{Card, CardConsts, SpawnedCard},
     deckbar::InitializeDeckbar,
     global::GameState,
     ingame_ui_root::InGameUiRoot,
@@ -19,6 +19,11 @@ struct LoseInformationRoot;
 #[derive(Component)]
 struct GoalNode;
 
+/// Units the player has to kill are outlined in this, and the goal row for them uses it too
+const MUST_KILL_COLOR: Srgba = RED_500;
+/// Units the player has to keep alive
+const MUST_PROTECT_COLOR: Srgba = GREEN_500;
+
 /// Goal Board is the informational ui that tells the player what they need to keep alive, and what
 /// they need to kill
 pub fn goal_board_plugin(app: &mut App) {
@@ -26,6 +31,10 @@ pub fn goal_board_plugin(app: &mut App) {
         .add_systems(
             FixedUpdate,
             update_board.run_if(in_state(GameState::InGame)),
+        )
+        .add_systems(
+            Update,
+            outline_goal_units.run_if(in_state(GameState::InGame)),
         );
 }
 
@@ -117,51 +126,134 @@ fn update_board(
         commands.entity(goal).despawn();
     }
 
-    let goal_node_style = Node {
-        width: Val::Px(50.),
-        max_width: Val::Px(100.0),
-        max_height: Val::Px(100.0),
-        margin: UiRect::horizontal(Val::Auto),
-        ..default()
+    let win_total = level_memory.win_condition.count_dead;
+    let win_row = spawn_goal_row(
+        &mut commands,
+        &asset_server,
+        *win_card,
+        win_total.saturating_sub(win_lose_death_progress.get_win()),
+        win_total,
+        MUST_KILL_COLOR,
+    );
+
+    let lose_total = level_memory.lose_condition.count_dead;
+    let lose_row = spawn_goal_row(
+        &mut commands,
+        &asset_server,
+        *lose_card,
+        lose_total.saturating_sub(win_lose_death_progress.get_lose()),
+        lose_total,
+        MUST_PROTECT_COLOR,
+    );
+
+    commands
+        .entity(*win_information_root)
+        .insert_children(1, &[win_row]);
+
+    commands
+        .entity(*lose_information_root)
+        .insert_children(1, &[lose_row]);
+}
+
+/// A mugshot of the card, how many of them have died out of how many the goal is about, and a bar
+/// filling up as they die
+fn spawn_goal_row(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    card: Card,
+    dead: u32,
+    total: u32,
+    color: Srgba,
+) -> Entity {
+    let fraction = if total == 0 {
+        1.
+    } else {
+        dead as f32 / total as f32
     };
 
-    let mut win_children: Vec<Entity> = Vec::new();
-    for _ in 0..win_lose_death_progress.get_win() {
-        win_children.push(
-            commands
-                .spawn((
-                    GoalNode,
-                    goal_node_style.clone(),
-                    ImageNode {
-                        image: asset_server.load(win_card.get_sprite_filepath()),
+    commands
+        .spawn((
+            GoalNode,
+            Node {
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(4.),
+                ..default()
+            },
+            children![
+                (
+                    Node {
+                        align_items: AlignItems::Center,
+                        column_gap: Val::Px(6.),
                         ..default()
                     },
-                ))
-                .id(),
-        )
-    }
-
-    let mut lose_children: Vec<Entity> = Vec::new();
-    for _ in 0..win_lose_death_progress.get_lose() {
-        lose_children.push(
-            commands
-                .spawn((
-                    GoalNode,
-                    goal_node_style.clone(),
-                    ImageNode {
-                        image: asset_server.load(lose_card.get_sprite_filepath()),
+                    children![
+                        (
+                            Node {
+                                width: Val::Px(40.),
+                                height: Val::Px(40.),
+                                border: UiRect::all(Val::Px(2.)),
+                                ..default()
+                            },
+                            BorderColor(color.into()),
+                            BorderRadius::all(Val::Px(6.)),
+                            ImageNode {
+                                image: asset_server.load(card.get_sprite_filepath()),
+                                ..default()
+                            },
+                        ),
+                        (
+                            Text::new(format!("{dead} of {total}")),
+                            TextFont::from_font_size(16.),
+                        )
+                    ]
+                ),
+                (
+                    Node {
+                        width: Val::Percent(100.),
+                        height: Val::Px(8.),
                         ..default()
                     },
-                ))
-                .id(),
-        )
-    }
+                    BackgroundColor(GRAY_700.into()),
+                    BorderRadius::all(Val::Px(4.)),
+                    children![(
+                        Node {
+                            width: Val::Percent(fraction * 100.),
+                            height: Val::Percent(100.),
+                            ..default()
+                        },
+                        BackgroundColor(color.into()),
+                        BorderRadius::all(Val::Px(4.)),
+                    )]
+                )
+            ],
+        ))
+        .id()
+}
 
-    commands
-        .entity(*win_information_root)
-        .insert_children(1, &win_children);
+/// Rings every unit the goals are about, red for the ones to kill and green for the ones to protect
+fn outline_goal_units(
+    cards: Query<(&SpawnedCard, &Transform)>,
+    level_memory: Res<LevelMemory>,
+    card_consts: Res<CardConsts>,
+    mut draw: Gizmos,
+) {
+    let win_card = *level_memory.get_win_card();
+    let lose_card = *level_memory.get_lose_card();
 
-    commands
-        .entity(*lose_information_root)
-        .insert_children(1, &lose_children);
+    for (card, transform) in cards {
+        let color = if **card == win_card {
+            MUST_KILL_COLOR
+        } else if **card == lose_card {
+            MUST_PROTECT_COLOR
+        } else {
+            continue;
+        };
+
+        let (width, height) = card.get_sprite_size(&card_consts);
+        draw.circle_2d(
+            Isometry2d::from_translation(transform.translation.truncate()),
+            width.max(height) / 2. + 4.,
+            color,
+        );
+    }
 }
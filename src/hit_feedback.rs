@@ -0,0 +1,144 @@
+use bevy::{color::palettes::tailwind::*, prelude::*};
+use std::{collections::HashSet, time::Duration};
+use DuckSlayer::delete_all;
+
+use crate::{
+    card::combat_events::{DamageDealt, DamageSource},
+    global::GameState,
+};
+
+const DAMAGE_NUMBER_LIFETIME: Duration = Duration::from_millis(900);
+const DAMAGE_NUMBER_RISE_SPEED: f32 = 60.;
+const HIT_FLASH_DURATION: Duration = Duration::from_millis(100);
+
+/// Whether hits show damage numbers and flash the sprite that was hit. Toggled in settings
+#[derive(Resource, Deref, DerefMut, PartialEq)]
+pub struct ShowHitFeedback(pub bool);
+
+impl Default for ShowHitFeedback {
+    fn default() -> Self {
+        ShowHitFeedback(true)
+    }
+}
+
+#[derive(Component)]
+#[require(Name::new("Damage Number"))]
+struct DamageNumber(Timer);
+
+/// Sprite is tinted past white while this is on it, then goes back to `original_color`
+#[derive(Component)]
+pub struct HitFlash {
+    timer: Timer,
+    original_color: Color,
+}
+
+impl HitFlash {
+    pub fn original_color(&self) -> Color {
+        self.original_color
+    }
+}
+
+pub fn hit_feedback_plugin(app: &mut App) {
+    app.add_systems(
+        FixedPostUpdate,
+        (spawn_damage_numbers, flash_hit_sprites)
+            .run_if(in_state(GameState::InGame).and(resource_equals(ShowHitFeedback(true)))),
+    )
+    .add_systems(Update, (float_damage_numbers, end_hit_flashes))
+    .add_systems(OnExit(GameState::InGame), delete_all::<DamageNumber>)
+    .init_resource::<ShowHitFeedback>();
+}
+
+fn spawn_damage_numbers(mut damage_dealt_evr: EventReader<DamageDealt>, mut commands: Commands) {
+    for damage_dealt in damage_dealt_evr.read() {
+        let color = match damage_dealt.source {
+            DamageSource::Attack => Color::WHITE,
+            DamageSource::Waterball => SKY_300.into(),
+        };
+
+        // Kills stand out and say what did it, so it's clear why something died
+        let (text, font_size, color) = if damage_dealt.lethal {
+            (
+                format!(
+                    "{:.0} KO by {}",
+                    damage_dealt.amount,
+                    damage_dealt.attacker_card.to_string()
+                ),
+                28.,
+                RED_400.into(),
+            )
+        } else {
+            (format!("{:.0}", damage_dealt.amount), 20., color)
+        };
+
+        commands.spawn((
+            DamageNumber(Timer::new(DAMAGE_NUMBER_LIFETIME, TimerMode::Once)),
+            Text2d::new(text),
+            TextFont::from_font_size(font_size),
+            TextColor(color),
+            Transform::from_translation(damage_dealt.victim_position.extend(10.)),
+        ));
+    }
+}
+
+pub fn flash_hit_sprites(
+    mut damage_dealt_evr: EventReader<DamageDealt>,
+    mut sprites: Query<(&mut Sprite, Option<&mut HitFlash>)>,
+    mut commands: Commands,
+) {
+    // HitFlash is only inserted once the commands are applied, so a second hit in the same batch
+    // would take the flash colour for the original one
+    let mut flashed = HashSet::new();
+
+    for damage_dealt in damage_dealt_evr.read() {
+        if !flashed.insert(damage_dealt.victim) {
+            continue;
+        }
+
+        let Ok((mut sprite, hit_flash)) = sprites.get_mut(damage_dealt.victim) else {
+            continue;
+        };
+
+        match hit_flash {
+            Some(mut hit_flash) => hit_flash.timer.reset(),
+            None => {
+                commands.entity(damage_dealt.victim).insert(HitFlash {
+                    timer: Timer::new(HIT_FLASH_DURATION, TimerMode::Once),
+                    original_color: sprite.color,
+                });
+                // Tinting past 1 brightens the texture instead of just leaving it as is
+                sprite.color = Color::linear_rgb(4., 4., 4.);
+            }
+        }
+    }
+}
+
+fn float_damage_numbers(
+    damage_numbers: Query<(Entity, &mut DamageNumber, &mut Transform, &mut TextColor)>,
+    time: Res<Time>,
+    mut commands: Commands,
+) {
+    for (entity, mut damage_number, mut transform, mut text_color) in damage_numbers {
+        damage_number.0.tick(time.delta());
+        if damage_number.0.finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        transform.translation.y += DAMAGE_NUMBER_RISE_SPEED * time.delta_secs();
+        text_color.set_alpha(damage_number.0.fraction_remaining());
+    }
+}
+
+fn end_hit_flashes(
+    hit_flashes: Query<(Entity, &mut HitFlash, &mut Sprite)>,
+    time: Res<Time>,
+    mut commands: Commands,
+) {
+    for (entity, mut hit_flash, mut sprite) in hit_flashes {
+        if hit_flash.timer.tick(time.delta()).finished() {
+            sprite.color = hit_flash.original_color;
+            commands.entity(entity).remove::<HitFlash>();
+        }
+    }
+}
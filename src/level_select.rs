@@ -1,4 +1,5 @@
 use bevy::{
+    asset::LoadState,
     color::palettes::{
         css::BLACK,
         tailwind::{BLUE_300, YELLOW_600},
@@ -10,14 +11,30 @@ use DuckSlayer::delete_all;
 use crate::{
     back_btn::{hide_back_btn, show_back_btn, PreviousScreen},
     global::GameState,
-    manage_level::{EnterLevel, Level},
+    load_error::{LoadError, ShowLoadError},
+    manage_level::{EnterLevel, Level, LevelSource},
 };
 
 #[derive(Component)]
 struct ForCleanup;
 
 #[derive(Resource)]
-struct LoadingLevel(Handle<Level>);
+struct LoadingLevel(Handle<Level>, i32);
+
+/// Number of the level being played, if it was picked from the level select
+#[derive(Resource, Default, Deref)]
+pub struct CurrentLevelNumber(Option<i32>);
+
+/// Level to start loading as soon as the level select is shown
+#[derive(Resource)]
+struct PendingLevel(i32);
+
+/// Every selectable level, loaded up front so their details can be shown on hover
+#[derive(Resource, Default)]
+struct LevelPreviews(Vec<Handle<Level>>);
+
+#[derive(Component)]
+struct LevelDetails;
 
 enum SelectableLevel {
     One,
@@ -63,19 +80,44 @@ impl SelectableLevel {
     }
 }
 
+/// Whether there's a level `i` on the level select
+pub fn level_exists(i: i32) -> bool {
+    SelectableLevel::from_i32(i).is_some()
+}
+
 #[derive(Component)]
 struct LevelSelectBtn(Option<SelectableLevel>);
 
+/// 1-based, the same as the number on the button
+#[derive(Component)]
+struct LevelNumber(i32);
+
 pub fn level_select(app: &mut App) {
     app.add_systems(
         OnEnter(GameState::LevelSelect),
-        (spawn_title, spawn_level_select_btns, show_back_btn),
+        (
+            spawn_title,
+            spawn_level_select_btns,
+            spawn_level_details,
+            load_level_previews,
+            show_back_btn,
+            load_pending_level,
+        ),
+    )
+    .add_systems(
+        FixedUpdate,
+        (
+            start_loading_level_on_btn_press,
+            load_levels,
+            show_level_details.run_if(in_state(GameState::LevelSelect)),
+        ),
     )
-    .add_systems(FixedUpdate, (start_loading_level_on_btn_press, load_levels))
     .add_systems(
         OnExit(GameState::LevelSelect),
-        (delete_all::<ForCleanup>, hide_back_btn),
-    );
+        (delete_all::<ForCleanup>, hide_back_btn, drop_level_previews),
+    )
+    .add_systems(OnExit(GameState::InGame), forget_current_level_number)
+    .init_resource::<CurrentLevelNumber>();
 }
 
 fn spawn_level_select_btns(mut commands: Commands) {
@@ -114,6 +156,7 @@ fn spawn_level_select_btns(mut commands: Commands) {
             .spawn((
                 Button,
                 LevelSelectBtn(selectable_level),
+                LevelNumber(i),
                 Node {
                     height: Val::Px(30.),
                     width: Val::Px(30.),
@@ -148,34 +191,159 @@ fn spawn_title(mut commands: Commands) {
     ));
 }
 
+fn spawn_level_details(mut commands: Commands) {
+    commands.spawn((
+        ForCleanup,
+        LevelDetails,
+        Text::default(),
+        TextColor(BLACK.into()),
+        TextFont::from_font_size(16.),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Vh(10.),
+            left: Val::Vw(10.),
+            max_width: Val::Vw(80.),
+            ..default()
+        },
+    ));
+}
+
+fn load_level_previews(asset_server: Res<AssetServer>, mut commands: Commands) {
+    let previews = (1..)
+        .map_while(SelectableLevel::from_i32)
+        .map(|level| asset_server.load(level.get_file()))
+        .collect();
+    commands.insert_resource(LevelPreviews(previews));
+}
+
+fn drop_level_previews(mut commands: Commands) {
+    commands.remove_resource::<LevelPreviews>();
+}
+
+fn show_level_details(
+    btn_interactions: Query<(&Interaction, &LevelNumber), Changed<Interaction>>,
+    mut details: Single<&mut Text, With<LevelDetails>>,
+    previews: Res<LevelPreviews>,
+    level_assets: Res<Assets<Level>>,
+) {
+    for (interaction, LevelNumber(i)) in btn_interactions {
+        if *interaction == Interaction::None {
+            details.0.clear();
+            continue;
+        }
+
+        let level = previews
+            .0
+            .get((i - 1) as usize)
+            .and_then(|handle| level_assets.get(handle));
+
+        details.0 = match level {
+            Some(level) => level_details(*i, level),
+            None if SelectableLevel::from_i32(*i).is_some() => format!("Level {i}"),
+            None => format!("Level {i} isn't made yet"),
+        };
+    }
+}
+
+fn level_details(i: i32, level: &Level) -> String {
+    let metadata = &level.metadata;
+    let mut details = metadata.display_name(&format!("Level {i}"));
+
+    if !metadata.author.is_empty() {
+        details += &format!(" by {}", metadata.author);
+    }
+    if let Some(difficulty) = metadata.difficulty {
+        details += &format!(" ({})", difficulty.to_string());
+    }
+    if !metadata.description.is_empty() {
+        details += &format!("\n{}", metadata.description);
+    }
+    if let Some(par) = metadata.par {
+        details += &format!("\nPar: {}", par.to_string());
+    }
+
+    details
+}
+
 fn start_loading_level_on_btn_press(
-    btn_interactions: Query<(&Interaction, &LevelSelectBtn), Changed<Interaction>>,
+    btn_interactions: Query<(&Interaction, &LevelSelectBtn, &LevelNumber), Changed<Interaction>>,
     asset_server: Res<AssetServer>,
     mut commands: Commands,
 ) {
-    for (interaction, LevelSelectBtn(level)) in btn_interactions {
+    for (interaction, LevelSelectBtn(level), LevelNumber(i)) in btn_interactions {
         let Some(level) = level else {
             return;
         };
 
         if *interaction == Interaction::Pressed {
-            commands.insert_resource(LoadingLevel(asset_server.load(level.get_file())));
+            commands.insert_resource(LoadingLevel(asset_server.load(level.get_file()), *i));
         }
     }
 }
 
+/// Goes to the level select and straight into level `i` from there
+pub struct PlaySelectableLevel(pub i32);
+impl Command for PlaySelectableLevel {
+    fn apply(self, world: &mut World) {
+        world.insert_resource(PendingLevel(self.0));
+        world
+            .resource_mut::<NextState<GameState>>()
+            .set(GameState::LevelSelect);
+    }
+}
+
+fn load_pending_level(
+    pending_level: Option<Res<PendingLevel>>,
+    asset_server: Res<AssetServer>,
+    mut commands: Commands,
+) {
+    let Some(pending_level) = pending_level else {
+        return;
+    };
+
+    if let Some(level) = SelectableLevel::from_i32(pending_level.0) {
+        commands.insert_resource(LoadingLevel(
+            asset_server.load(level.get_file()),
+            pending_level.0,
+        ));
+    }
+    commands.remove_resource::<PendingLevel>();
+}
+
+fn forget_current_level_number(mut current_level_number: ResMut<CurrentLevelNumber>) {
+    current_level_number.0 = None;
+}
+
 fn load_levels(
-    loading_level: Option<ResMut<LoadingLevel>>,
+    loading_level: Option<Res<LoadingLevel>>,
     mut commands: Commands,
-    mut level_assets: ResMut<Assets<Level>>,
+    level_assets: Res<Assets<Level>>,
     mut previous_screen: ResMut<PreviousScreen>,
+    asset_server: Res<AssetServer>,
 ) {
     let Some(loading_level) = loading_level else {
         return;
     };
 
-    if let Some(loaded_level) = level_assets.remove(loading_level.0.id()) {
+    if let Some(LoadState::Failed(err)) = asset_server.get_load_state(loading_level.0.id()) {
+        let path = loading_level
+            .0
+            .path()
+            .map_or(String::from("level"), |path| path.to_string());
+
+        commands.queue(ShowLoadError(LoadError::Asset {
+            path,
+            message: err.to_string(),
+        }));
+        commands.remove_resource::<LoadingLevel>();
+        return;
+    }
+
+    // The asset is shared with the previews, so it's copied rather than taken
+    if let Some(loaded_level) = level_assets.get(loading_level.0.id()).cloned() {
         commands.queue(EnterLevel(loaded_level));
+        commands.insert_resource(LevelSource::Asset(loading_level.0.clone()));
+        commands.insert_resource(CurrentLevelNumber(Some(loading_level.1)));
         **previous_screen = Some(GameState::LevelSelect);
         commands.remove_resource::<LoadingLevel>();
     }
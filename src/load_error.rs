@@ -0,0 +1,165 @@
+use bevy::prelude::*;
+use bevy_egui::{
+    egui::{self, Color32},
+    EguiContextPass, EguiContexts,
+};
+use serde::de::DeserializeOwned;
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+/// Everything that can go wrong turning a file into a level or constants
+#[derive(Debug)]
+pub enum LoadError {
+    Read {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+    NotUtf8 {
+        path: PathBuf,
+    },
+    Parse {
+        path: PathBuf,
+        field: String,
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    Migration {
+        path: PathBuf,
+        message: String,
+    },
+    Asset {
+        path: String,
+        message: String,
+    },
+    ShareCode {
+        message: String,
+    },
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Read { path, error } => {
+                write!(f, "Couldn't read {}: {error}", path.display())
+            }
+            LoadError::NotUtf8 { path } => {
+                write!(f, "{} isn't a text file", path.display())
+            }
+            LoadError::Parse {
+                path,
+                field,
+                line,
+                column,
+                message,
+            } => {
+                write!(f, "{}", path.display())?;
+                // Values that were migrated don't have a position in the file anymore
+                if *line != 0 {
+                    write!(f, ", line {line} column {column}")?;
+                }
+                // serde_path_to_error uses "." for the root of the file
+                if field != "." {
+                    write!(f, ", in `{field}`")?;
+                }
+                write!(f, ": {message}")
+            }
+            LoadError::Migration { path, message } => {
+                write!(f, "Couldn't upgrade {}: {message}", path.display())
+            }
+            LoadError::Asset { path, message } => {
+                write!(f, "Couldn't load {path}: {message}")
+            }
+            LoadError::ShareCode { message } => {
+                write!(f, "That level code doesn't work, {message}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+pub fn read_json_file<T: DeserializeOwned>(path: &Path) -> Result<T, LoadError> {
+    let bytes = std::fs::read(path).map_err(|error| LoadError::Read {
+        path: path.to_path_buf(),
+        error,
+    })?;
+
+    parse_json(path, &bytes)
+}
+
+pub fn parse_json<T: DeserializeOwned>(path: &Path, bytes: &[u8]) -> Result<T, LoadError> {
+    let text = std::str::from_utf8(bytes).map_err(|_| LoadError::NotUtf8 {
+        path: path.to_path_buf(),
+    })?;
+
+    let deserializer = &mut serde_json::Deserializer::from_str(text);
+    serde_path_to_error::deserialize(deserializer).map_err(|err| {
+        let field = err.path().to_string();
+        let error = err.into_inner();
+
+        // serde_json tacks the position onto the end of the message, it's shown separately
+        let message = error.to_string();
+        let message = match message.rsplit_once(" at line ") {
+            Some((message, _)) => message.to_string(),
+            None => message,
+        };
+
+        LoadError::Parse {
+            path: path.to_path_buf(),
+            field,
+            line: error.line(),
+            column: error.column(),
+            message,
+        }
+    })
+}
+
+#[derive(Resource, Default, Deref, DerefMut)]
+struct LoadErrors(Vec<LoadError>);
+
+pub fn load_error_plugin(app: &mut App) {
+    app.add_systems(
+        EguiContextPass,
+        show_load_error.run_if(|load_errors: Res<LoadErrors>| !load_errors.is_empty()),
+    )
+    .init_resource::<LoadErrors>();
+}
+
+/// Tells the player why a file couldn't be loaded
+pub struct ShowLoadError(pub LoadError);
+impl Command for ShowLoadError {
+    fn apply(self, world: &mut World) {
+        warn!("{}", self.0);
+        world.resource_mut::<LoadErrors>().push(self.0);
+    }
+}
+
+fn show_load_error(mut contexts: EguiContexts, mut load_errors: ResMut<LoadErrors>) {
+    let mut dismissed = false;
+
+    egui::Window::new("Couldn't load file")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, (0., 0.))
+        .show(contexts.ctx_mut(), |ui| {
+            ui.colored_label(Color32::LIGHT_RED, load_errors[0].to_string());
+
+            if load_errors.len() > 1 {
+                ui.label(format!(
+                    "{} more errors after this one",
+                    load_errors.len() - 1
+                ));
+            }
+
+            if ui.button("OK").clicked() {
+                dismissed = true;
+            }
+        });
+
+    if dismissed {
+        load_errors.remove(0);
+    }
+}
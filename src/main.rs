@@ -3,23 +3,35 @@ use bevy::{prelude::*, window::WindowResolution};
 mod asset_load_schedule;
 mod back_btn;
 mod card;
+mod cli;
 mod debug;
 mod debug_ui;
 mod deckbar;
 mod global;
 mod goal_board;
+mod hit_feedback;
 mod ingame_ui_root;
 mod level_select;
+mod load_error;
 mod manage_level;
+mod particles;
+mod placement;
+mod results_screen;
 mod settings_screen;
+mod terrain;
 mod titlescreen;
 mod volume_settings;
 mod widgets;
+mod y_sort;
 
 use bevy_egui::EguiPlugin;
-use std::env;
+use cli::CliCommand;
 
 fn main() {
+    if let Some(CliCommand::Check { levels }) = &cli::cli().command {
+        std::process::exit(cli::check_levels(levels));
+    }
+
     App::new()
         .add_systems(Startup, setup_camera)
         .add_plugins(
@@ -28,6 +40,11 @@ fn main() {
                     //level: bevy::log::Level::DEBUG,
                     ..default()
                 })
+                .set(AssetPlugin {
+                    // Levels are reloaded when they're edited while playing
+                    watch_for_changes_override: Some(true),
+                    ..default()
+                })
                 .set(WindowPlugin {
                     primary_window: Some(Window {
                         resolution: get_resolution(),
@@ -58,6 +75,17 @@ fn main() {
             debug_ui::debug_ui_plugin,
             widgets::widgets_plugin,
         ))
+        // Plugin tuples can't be longer than 15
+        .add_plugins((
+            terrain::terrain_plugin,
+            load_error::load_error_plugin,
+            cli::cli_plugin,
+            results_screen::results_screen_plugin,
+            hit_feedback::hit_feedback_plugin,
+            particles::particles_plugin,
+            y_sort::y_sort_plugin,
+            placement::placement_plugin,
+        ))
         .run();
 }
 
@@ -65,25 +93,10 @@ fn setup_camera(mut commands: Commands) {
     commands.spawn(Camera2d);
 }
 
-// Resolution is incorrectly found on joel's computer and is easier to manually set resolution
-// instead of messing with xserver/wayland settings
 fn get_resolution() -> WindowResolution {
-    let mut is_joels_computer = false;
-    if let Ok(joels_comuter_env) = env::var("JOELS_COMPUTER") {
-        if joels_comuter_env == "true" {
-            is_joels_computer = true;
-        }
-    }
-
-    let Ok(current_computer) = env::var("CURRENT_COMPUTER") else {
-        return default();
-    };
-
-    let mut resolution = WindowResolution::default();
-    if is_joels_computer {
-        let scale_factor = if current_computer == "hp" { 1.406 } else { 1.0 };
-
-        resolution = resolution.with_scale_factor_override(scale_factor);
+    let resolution = WindowResolution::default();
+    match cli::cli().scale_factor {
+        Some(scale_factor) => resolution.with_scale_factor_override(scale_factor),
+        None => resolution,
     }
-    resolution
 }
@@ -1,19 +1,30 @@
 mod editor_ui;
 mod game_controls;
 mod game_messages;
+mod hot_reload;
 mod level;
+mod level_stats;
+mod timeline;
 
-use bevy::prelude::*;
+use bevy::{ecs::system::RunSystemOnce, prelude::*};
+pub use game_controls::RestartLevel;
 pub use game_messages::set_message;
-pub use level::Level;
+pub use hot_reload::{LevelSource, WatchedConstsFile, WatchedFile};
+pub use level::{
+    decode_share_code, encode_share_code, read_level_file, Level, CURRENT_LEVEL_VERSION,
+};
+pub use level_stats::LevelStats;
 use strum::IntoEnumIterator;
 use DuckSlayer::{delete_all, remove_resource};
 
 use crate::{
     back_btn::{hide_back_btn, show_back_btn},
-    card::{Card, CardDeath, SpawnCard},
+    card::{combat_events::clear_combat_log, Card, CardConsts, CardDeath, SpawnCard},
     deckbar::{clear_deckbar, PushToDeckbar},
     global::{GameState, ImageHandles, InEditorRes},
+    placement::PlacementZones,
+    terrain::Terrain,
+    y_sort::YSort,
 };
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, States)]
@@ -22,6 +33,18 @@ pub enum IsPaused {
     False,
 }
 
+/// Exists while a single tick is being stepped through, the game stays paused for it
+#[derive(Resource)]
+pub struct SteppingOneTick;
+
+/// Run condition for everything that moves the simulation forward
+pub fn simulation_running(
+    is_paused: Res<State<IsPaused>>,
+    stepping: Option<Res<SteppingOneTick>>,
+) -> bool {
+    **is_paused == IsPaused::False || stepping.is_some()
+}
+
 #[derive(Resource, Debug, PartialEq, Clone)]
 pub struct WinLoseDeathProgress {
     // reaching 0 is win or loss
@@ -61,6 +84,7 @@ impl LevelMemory {
 }
 
 #[derive(Component, Default)]
+#[require(YSort)]
 pub struct LevelEntity;
 
 #[derive(Component)]
@@ -74,6 +98,9 @@ pub fn manage_level(app: &mut App) {
         .add_plugins(editor_ui::editor_ui_plugin)
         .add_plugins(level::level_plugin)
         .add_plugins(game_controls::game_controls_plugin)
+        .add_plugins(hot_reload::hot_reload_plugin)
+        .add_plugins(timeline::timeline_plugin)
+        .add_plugins(level_stats::level_stats_plugin)
         .add_systems(
             OnEnter(GameState::InGame),
             (spawn_arena_background, load_card_sprites, show_back_btn),
@@ -83,14 +110,8 @@ pub fn manage_level(app: &mut App) {
             (win_or_lose_on_conditions)
                 .run_if(in_state(GameState::InGame).and(in_state(LevelProgress::Null))),
         )
-        .add_systems(
-            OnEnter(LevelProgress::GameOver),
-            (pause, set_message("You lost :(")),
-        )
-        .add_systems(
-            OnEnter(LevelProgress::GameWon),
-            (pause, set_message("You won! :)")),
-        )
+        .add_systems(OnEnter(LevelProgress::GameOver), pause)
+        .add_systems(OnEnter(LevelProgress::GameWon), pause)
         .add_systems(
             OnExit(GameState::InGame),
             (
@@ -158,6 +179,11 @@ fn spawn_entities_from_level_memory(level: Res<LevelMemory>, mut commands: Comma
 fn spawn_entities_from_level(level: &Level, commands: &mut Commands) {
     commands.run_system_cached(clear_deckbar);
     commands.run_system_cached(delete_all::<LevelEntity>);
+    commands.run_system_cached(timeline::clear_timeline);
+    commands.run_system_cached(clear_combat_log);
+    commands.run_system_cached(level_stats::clear_level_stats);
+    commands.insert_resource(Terrain::from_tiles(&level.terrain));
+    commands.insert_resource(PlacementZones(level.placement_zones.clone()));
 
     for (card, position) in &level.cards {
         commands.queue(SpawnCard::new(*card, *position));
@@ -188,6 +214,7 @@ impl Command for Pause {
 pub struct EnterLevel(pub Level);
 impl Command for EnterLevel {
     fn apply(self, world: &mut World) -> () {
+        *world.resource_mut::<CardConsts>() = CardConsts::from_overrides(&self.consts_overrides);
         spawn_entities_from_level(&self, &mut world.commands());
         world
             .resource_mut::<NextState<GameState>>()
@@ -202,6 +229,19 @@ impl Command for EnterLevel {
     }
 }
 
+/// Swaps out the level in memory while staying in game, respawning everything from the new level
+pub struct ReplaceLevel(pub Level);
+impl Command for ReplaceLevel {
+    fn apply(self, world: &mut World) -> () {
+        *world.resource_mut::<CardConsts>() = CardConsts::from_overrides(&self.0.consts_overrides);
+        world.resource_mut::<LevelMemory>().0 = self.0;
+
+        let _ = world.run_system_once(delete_all::<LevelEntity>);
+        let _ = world.run_system_once(clear_deckbar);
+        let _ = world.run_system_once(spawn_entities_from_level_memory);
+    }
+}
+
 fn set_in_editor_false(mut in_editor: ResMut<InEditorRes>) {
     **in_editor = false;
 }
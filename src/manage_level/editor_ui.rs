@@ -1,7 +1,12 @@
+mod autosave;
 mod saving_loading_levels;
+mod snapshots;
+mod terrain_painting;
+mod zone_drawing;
 
 use crate::manage_level::game_controls;
 pub use crate::manage_level::game_controls::save_indicator;
+use autosave::autosave_plugin;
 use bevy::{ecs::system::RunSystemOnce, prelude::*};
 use bevy_egui::{
     egui::{self, ComboBox, Slider, Ui},
@@ -9,30 +14,47 @@ use bevy_egui::{
 };
 use saving_loading_levels::{
     saving_loading_levels_plugin, LoadCardConstsWithFileDialog, LoadLevelWithFileDialog,
-    SaveCardConstsWithFileDialog, SaveLevelWithFileDialog,
+    SaveCardConstsWithFileDialog, SaveCombatLogWithFileDialog, SaveLevelWithFileDialog,
+    UseNativeDialogs,
 };
+use snapshots::{snapshots_plugin, snapshots_ui, Snapshots};
 use strum::IntoEnumIterator;
+pub use terrain_painting::TerrainBrush;
+use terrain_painting::{terrain_brush_ui, terrain_painting_plugin};
+pub use zone_drawing::ZoneBrush;
+use zone_drawing::{zone_brush_ui, zone_drawing_plugin};
 use DuckSlayer::delete_all;
 
 use crate::{
-    card::{Card, CardConsts},
+    card::{combat_events::CombatLog, Card, CardConsts},
     deckbar::{clear_deckbar, PushToDeckbar},
     global::{in_editor, GameState},
+    placement::PlacementZones,
 };
 
 use super::{
+    encode_share_code,
+    game_messages::{ShowToast, ToastSeverity},
+    level::{Difficulty, LevelMetadata, Par},
     pause, reset_level_progress, save_level_to_memory, spawn_entities_from_level_memory,
-    LevelEntity, LevelMemory, Pause,
+    timeline::{timeline_ui, Timeline},
+    Level, LevelEntity, LevelMemory, Pause,
 };
 
 #[derive(Resource, Default)]
 struct IsConstantsWindowOpen(bool);
 
 pub fn editor_ui_plugin(app: &mut App) {
-    app.add_plugins(saving_loading_levels_plugin)
-        .add_systems(EguiContextPass, create_editor_window.run_if(in_editor))
-        .add_systems(OnExit(GameState::InGame), cleanup)
-        .init_resource::<IsConstantsWindowOpen>();
+    app.add_plugins((
+        saving_loading_levels_plugin,
+        terrain_painting_plugin,
+        zone_drawing_plugin,
+        autosave_plugin,
+        snapshots_plugin,
+    ))
+    .add_systems(EguiContextPass, create_editor_window.run_if(in_editor))
+    .add_systems(OnExit(GameState::InGame), cleanup)
+    .init_resource::<IsConstantsWindowOpen>();
 }
 
 fn create_editor_window(
@@ -40,6 +62,13 @@ fn create_editor_window(
     mut card_consts: ResMut<CardConsts>,
     mut level: ResMut<LevelMemory>,
     mut is_constants_window_open: ResMut<IsConstantsWindowOpen>,
+    mut terrain_brush: ResMut<TerrainBrush>,
+    mut zone_brush: ResMut<ZoneBrush>,
+    mut placement_zones: ResMut<PlacementZones>,
+    mut snapshots: ResMut<Snapshots>,
+    mut timeline: ResMut<Timeline>,
+    combat_log: Res<CombatLog>,
+    mut use_native_dialogs: ResMut<UseNativeDialogs>,
     mut commands: Commands,
 ) {
     egui::Window::new("Editor")
@@ -65,7 +94,19 @@ fn create_editor_window(
                 })
             }
 
+            ui.collapsing("Snapshots", |ui| {
+                snapshots_ui(ui, &mut snapshots, &mut commands);
+            });
+
+            ui.collapsing("Timeline", |ui| {
+                timeline_ui(ui, &mut timeline, &mut commands);
+            });
+
             ui.heading("Saving to file");
+            ui.add_enabled(
+                cfg!(feature = "native-dialogs"),
+                egui::Checkbox::new(&mut **use_native_dialogs, "Use native file dialogs"),
+            );
             if ui.button("Save level to file").clicked() {
                 commands.queue(SaveLevelWithFileDialog);
                 commands.queue(Pause);
@@ -76,6 +117,27 @@ fn create_editor_window(
                 commands.queue(LoadLevelWithFileDialog);
             }
 
+            if ui.button("Copy level code").clicked() {
+                let ctx = ui.ctx().clone();
+                commands.queue(move |world: &mut World| {
+                    ctx.copy_text(encode_share_code(&Level::get_current(world)));
+                    ShowToast::new(ToastSeverity::Success, "Level code copied to clipboard")
+                        .apply(world);
+                });
+            }
+
+            let combat_log_text = format!("Export combat log ({} events)", combat_log.len());
+            if ui.button(combat_log_text).clicked() {
+                commands.queue(Pause);
+                commands.queue(SaveCombatLogWithFileDialog);
+            }
+
+            ui.collapsing("Level info", |ui| {
+                if metadata_ui(ui, &mut level.metadata) {
+                    commands.run_system_cached(save_indicator::set_not_saved);
+                }
+            });
+
             ui.heading("Win/Lose conditions");
             ComboBox::from_label("Win Condition")
                 .selected_text(level.win_condition.card.to_string())
@@ -111,6 +173,22 @@ fn create_editor_window(
                 commands.run_system_cached(reset_level_progress);
             }
 
+            let was_drawing_zones = zone_brush.is_drawing;
+            ui.heading("Terrain");
+            terrain_brush_ui(ui, &mut terrain_brush);
+
+            ui.heading("Placement zones");
+            zone_brush_ui(ui, &mut zone_brush, &mut placement_zones, &mut commands);
+
+            // Both are done by dragging, so only one can be on. The one just turned on wins
+            if terrain_brush.is_painting && zone_brush.is_drawing {
+                if was_drawing_zones {
+                    zone_brush.is_drawing = false;
+                } else {
+                    terrain_brush.is_painting = false;
+                }
+            }
+
             ui.heading("Toggles");
             if ui.button("Toggle constants window").clicked() {
                 is_constants_window_open.0 = !is_constants_window_open.0;
@@ -123,21 +201,29 @@ fn create_editor_window(
             .default_pos((0., 400.))
             .show(contexts.ctx_mut(), |ui| {
                 ui.label("Some constants only take effect for newly spawned cards");
+                ui.label("Constants that differ from the defaults are saved with the level");
                 let const_edit =
                     |ui: &mut Ui, desc: &'static str, constant: &mut f32, max_val: f32| {
-                        ui.add(Slider::new(constant, 0.0..=max_val).text(desc));
+                        ui.add(Slider::new(constant, 0.0..=max_val).text(desc))
+                            .changed()
                     };
+                let mut changed = false;
 
                 ui.heading("Waterball");
-                const_edit(ui, "Radius", &mut card_consts.waterball.radius, 250.);
-                const_edit(ui, "Damage", &mut card_consts.waterball.damage, 250.);
+                changed |= const_edit(ui, "Radius", &mut card_consts.waterball.radius, 250.);
+                changed |= const_edit(ui, "Damage", &mut card_consts.waterball.damage, 250.);
 
                 ui.heading("Nest");
-                const_edit(ui, "Damage", &mut card_consts.nest.damage, 100.);
-                const_edit(ui, "Range", &mut card_consts.nest.range, 1000.);
+                changed |= const_edit(ui, "Damage", &mut card_consts.nest.damage, 100.);
+                changed |= const_edit(ui, "Range", &mut card_consts.nest.range, 1000.);
 
                 ui.heading("Farmer");
-                const_edit(ui, "Speed", &mut card_consts.farmer.speed, 100.);
+                changed |= const_edit(ui, "Speed", &mut card_consts.farmer.speed, 100.);
+
+                if changed {
+                    commands.run_system_cached(save_indicator::set_not_saved);
+                    commands.run_system_cached(game_controls::allow_game_reset);
+                }
 
                 if ui.button("Save current constants to file").clicked() {
                     commands.queue(Pause);
@@ -152,6 +238,80 @@ fn create_editor_window(
     }
 }
 
+/// Returns true if any of the metadata was changed
+fn metadata_ui(ui: &mut Ui, metadata: &mut LevelMetadata) -> bool {
+    let mut changed = false;
+
+    ui.horizontal(|ui| {
+        ui.label("Name");
+        changed |= ui.text_edit_singleline(&mut metadata.name).changed();
+    });
+    ui.horizontal(|ui| {
+        ui.label("Author");
+        changed |= ui.text_edit_singleline(&mut metadata.author).changed();
+    });
+    ui.label("Description");
+    changed |= ui.text_edit_multiline(&mut metadata.description).changed();
+
+    ComboBox::from_label("Difficulty")
+        .selected_text(
+            metadata
+                .difficulty
+                .map_or("None".to_string(), |difficulty| difficulty.to_string()),
+        )
+        .show_ui(ui, |ui| {
+            changed |= ui
+                .selectable_value(&mut metadata.difficulty, None, "None")
+                .changed();
+            for difficulty in Difficulty::iter() {
+                changed |= ui
+                    .selectable_value(
+                        &mut metadata.difficulty,
+                        Some(difficulty),
+                        difficulty.to_string(),
+                    )
+                    .changed();
+            }
+        });
+
+    let mut has_par = metadata.par.is_some();
+    changed |= ui.checkbox(&mut has_par, "Par").changed();
+    match (has_par, &mut metadata.par) {
+        (true, None) => metadata.par = Some(Par::default()),
+        (false, Some(_)) => metadata.par = None,
+        _ => {}
+    }
+    if let Some(par) = &mut metadata.par {
+        changed |= ui
+            .add(Slider::new(&mut par.cards_used, 1..=20).text("Cards used"))
+            .changed();
+        changed |= ui
+            .add(Slider::new(&mut par.time_secs, 1.0..=300.).text("Time (s)"))
+            .changed();
+    }
+
+    ui.label("Hints, revealed in order");
+    let mut to_remove: Option<usize> = None;
+    for (i, hint) in metadata.hints.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            changed |= ui.text_edit_singleline(hint).changed();
+            if ui.small_button("Remove").clicked() {
+                to_remove = Some(i);
+            }
+        });
+    }
+    if let Some(to_remove) = to_remove {
+        metadata.hints.remove(to_remove);
+        changed = true;
+    }
+    if ui.button("Add hint").clicked() {
+        metadata.hints.push(String::new());
+        changed = true;
+    }
+
+    changed
+}
+
 fn create_push_to_deckbar_btns(ui: &mut Ui, commands: &mut Commands) {
     for card in Card::iter() {
         let push_to_deck_btn =
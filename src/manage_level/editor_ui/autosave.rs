@@ -0,0 +1,162 @@
+use bevy::{ecs::system::RunSystemOnce, prelude::*};
+use bevy_egui::{egui, EguiContextPass, EguiContexts};
+use std::{path::PathBuf, time::Duration};
+
+use crate::{
+    global::{in_editor, user_data_dir, GameState},
+    manage_level::{
+        game_messages::{ShowToast, ToastSeverity},
+        read_level_file, IsPaused, Level, Pause, ReplaceLevel,
+    },
+};
+
+use super::save_indicator::{self, HasUnsavedEdits};
+
+const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Resource)]
+struct AutosaveTimer(Timer);
+
+/// A level recovered from a previous session that the player hasn't decided what to do with yet.
+/// Autosaving is held off while this exists so the recovery file isn't overwritten
+#[derive(Resource)]
+struct RecoveryOffer(Level);
+
+pub fn autosave_plugin(app: &mut App) {
+    app.add_systems(
+        OnEnter(GameState::InGame),
+        (reset_autosave_timer, offer_recovery).run_if(in_editor),
+    )
+    .add_systems(
+        Update,
+        (
+            tick_autosave_timer,
+            // While the simulation runs the arena isn't the level that's being designed
+            autosave.run_if(autosave_is_due.and(in_state(IsPaused::True))),
+        )
+            .chain()
+            .run_if(
+                in_state(GameState::InGame)
+                    .and(in_editor)
+                    .and(not(resource_exists::<RecoveryOffer>)),
+            ),
+    )
+    .add_systems(
+        EguiContextPass,
+        show_recovery_offer.run_if(resource_exists::<RecoveryOffer>),
+    )
+    .add_systems(OnExit(GameState::InGame), withdraw_recovery_offer)
+    .insert_resource(AutosaveTimer(Timer::new(
+        AUTOSAVE_INTERVAL,
+        TimerMode::Repeating,
+    )));
+}
+
+fn recovery_file() -> Option<PathBuf> {
+    user_data_dir().map(|dir| dir.join("recovery.level.json"))
+}
+
+/// Called once the level is safely in a file the player chose, there's nothing left to recover
+pub fn remove_recovery_file() {
+    if let Some(recovery_file) = recovery_file() {
+        if recovery_file.exists() {
+            if let Err(err) = std::fs::remove_file(&recovery_file) {
+                warn!("Couldn't remove editor recovery file: {err}");
+            }
+        }
+    }
+}
+
+fn reset_autosave_timer(mut timer: ResMut<AutosaveTimer>) {
+    timer.0.reset();
+}
+
+fn tick_autosave_timer(mut timer: ResMut<AutosaveTimer>, time: Res<Time<Real>>) {
+    timer.0.tick(time.delta());
+}
+
+fn autosave_is_due(timer: Res<AutosaveTimer>, has_unsaved_edits: Res<HasUnsavedEdits>) -> bool {
+    timer.0.just_finished() && **has_unsaved_edits
+}
+
+fn autosave(world: &mut World, mut last_autosave: Local<String>) {
+    let Some(recovery_file) = recovery_file() else {
+        return;
+    };
+
+    let level = serde_json::to_string_pretty(&Level::get_current(world)).unwrap();
+    if *last_autosave == level {
+        return;
+    }
+
+    let result = recovery_file
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|_| std::fs::write(&recovery_file, &level));
+
+    match result {
+        Ok(_) => *last_autosave = level,
+        Err(err) => {
+            warn!("Couldn't autosave the level: {err}");
+            ShowToast::new(ToastSeverity::Warning, "Couldn't autosave the level").apply(world);
+        }
+    }
+}
+
+fn offer_recovery(mut commands: Commands) {
+    let Some(recovery_file) = recovery_file() else {
+        return;
+    };
+
+    if !recovery_file.exists() {
+        return;
+    }
+
+    match read_level_file(&recovery_file) {
+        Ok(loaded) => commands.insert_resource(RecoveryOffer(loaded.level)),
+        Err(err) => {
+            warn!("Editor recovery file is unusable, removing it: {err}");
+            remove_recovery_file();
+        }
+    }
+}
+
+fn show_recovery_offer(
+    mut contexts: EguiContexts,
+    recovery_offer: Res<RecoveryOffer>,
+    mut commands: Commands,
+) {
+    egui::Window::new("Recover unsaved level?")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, (0., 0.))
+        .show(contexts.ctx_mut(), |ui| {
+            ui.label("The editor has an autosave of a level that was never saved to a file.");
+            ui.label(format!(
+                "It has {} cards in the arena and {} in the deck.",
+                recovery_offer.0.cards.len(),
+                recovery_offer.0.starting_deckbar.len()
+            ));
+
+            ui.horizontal(|ui| {
+                if ui.button("Restore").clicked() {
+                    let level = recovery_offer.0.clone();
+                    commands.queue(Pause);
+                    commands.queue(move |world: &mut World| {
+                        ReplaceLevel(level).apply(world);
+                        let _ = world.run_system_once(save_indicator::set_not_saved);
+                    });
+                    commands.remove_resource::<RecoveryOffer>();
+                }
+
+                if ui.button("Discard").clicked() {
+                    remove_recovery_file();
+                    commands.remove_resource::<RecoveryOffer>();
+                }
+            });
+        });
+}
+
+fn withdraw_recovery_offer(mut commands: Commands) {
+    commands.remove_resource::<RecoveryOffer>();
+}
@@ -1,42 +1,127 @@
-use bevy::{
-    ecs::system::RunSystemOnce,
-    prelude::*,
-    tasks::{futures_lite::future, AsyncComputeTaskPool, Task},
-};
+pub mod file_browser;
+
+#[cfg(feature = "native-dialogs")]
+use bevy::tasks::{futures_lite::future, AsyncComputeTaskPool, Task};
+use bevy::{ecs::system::RunSystemOnce, prelude::*};
+use bevy_egui::{egui, EguiContextPass, EguiContexts};
+use file_browser::{file_browser_plugin, FileBrowserRequest};
+#[cfg(feature = "native-dialogs")]
 use rfd::FileDialog;
 use std::path::PathBuf;
-use DuckSlayer::delete_all;
 
 use crate::{
-    card::CardConsts,
-    deckbar::clear_deckbar,
-    manage_level::{spawn_entities_from_level_memory, Level, LevelEntity, LevelMemory},
+    card::{combat_events::CombatLog, CardConsts},
+    global::GameState,
+    load_error::{read_json_file, ShowLoadError},
+    manage_level::{
+        game_messages::{ShowToast, ToastSeverity},
+        read_level_file, Level, LevelSource, ReplaceLevel, WatchedConstsFile, WatchedFile,
+        CURRENT_LEVEL_VERSION,
+    },
 };
 
-use super::save_indicator;
+use super::{autosave::remove_recovery_file, save_indicator};
+
+/// Whether files are picked with the desktop's own dialog instead of the in game file browser.
+/// Native dialogs need a desktop portal, so this can be switched off when one isn't around
+#[derive(Resource, Deref, DerefMut)]
+pub struct UseNativeDialogs(bool);
+
+impl Default for UseNativeDialogs {
+    fn default() -> Self {
+        UseNativeDialogs(cfg!(feature = "native-dialogs"))
+    }
+}
+
+/// A level that had to be upgraded from an older version when it was loaded
+#[derive(Resource)]
+struct ResaveOffer {
+    path: PathBuf,
+    level: Level,
+    from_version: u32,
+}
 
 pub fn saving_loading_levels_plugin(app: &mut App) {
+    app.add_plugins(file_browser_plugin)
+        .add_systems(
+            EguiContextPass,
+            show_resave_offer.run_if(resource_exists::<ResaveOffer>),
+        )
+        .add_systems(OnExit(GameState::InGame), withdraw_resave_offer)
+        .init_resource::<UseNativeDialogs>();
+
+    #[cfg(feature = "native-dialogs")]
     app.add_systems(FixedUpdate, poll_filepicker_completion);
 }
 
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum FileKind {
+    Level,
+    CardConsts,
+    CombatLog,
+}
+
+impl FileKind {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            FileKind::Level => "level.json",
+            FileKind::CardConsts => "consts.json",
+            FileKind::CombatLog => "combat-log.json",
+        }
+    }
+
+    pub fn to_string(&self) -> String {
+        match self {
+            FileKind::Level => "level".to_string(),
+            FileKind::CardConsts => "constants".to_string(),
+            FileKind::CombatLog => "combat log".to_string(),
+        }
+    }
+
+    fn placeholder_file_name(&self) -> String {
+        format!("placeholder.{}", self.extension())
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum FileAction {
+    Save,
+    Open,
+}
+
+#[cfg(feature = "native-dialogs")]
 #[derive(Component)]
 struct PickingFile(Task<Option<PathBuf>>);
 
 #[derive(Event)]
 struct FinishedPickingFile(PathBuf);
 
-pub struct SaveLevelWithFileDialog;
-impl Command for SaveLevelWithFileDialog {
-    fn apply(self, world: &mut World) {
+/// Asks the player for a file. [`FinishedPickingFile`] is triggered on the returned entity once
+/// they've picked one, and the entity is despawned if they cancel
+fn pick_file(world: &mut World, kind: FileKind, action: FileAction) -> EntityWorldMut<'_> {
+    #[cfg(feature = "native-dialogs")]
+    if **world.resource::<UseNativeDialogs>() {
         let thread_pool = AsyncComputeTaskPool::get();
         let task = thread_pool.spawn(async move {
-            FileDialog::new()
-                .set_file_name("placeholder.level.json")
-                .add_filter("JSON", &["level.json"])
-                .save_file()
+            let dialog = FileDialog::new().add_filter("JSON", &[kind.extension()]);
+            match action {
+                FileAction::Save => dialog
+                    .set_file_name(kind.placeholder_file_name())
+                    .save_file(),
+                FileAction::Open => dialog.pick_file(),
+            }
         });
 
-        world.spawn(PickingFile(task)).observe(
+        return world.spawn(PickingFile(task));
+    }
+
+    world.spawn(FileBrowserRequest { kind, action })
+}
+
+pub struct SaveLevelWithFileDialog;
+impl Command for SaveLevelWithFileDialog {
+    fn apply(self, world: &mut World) {
+        pick_file(world, FileKind::Level, FileAction::Save).observe(
             |trigger: Trigger<FinishedPickingFile>, world: &mut World| {
                 let level = Level::get_current(world);
                 let picked_file = &trigger.0;
@@ -44,8 +129,16 @@ impl Command for SaveLevelWithFileDialog {
                 let result =
                     std::fs::write(picked_file, serde_json::to_string_pretty(&level).unwrap());
 
-                if let Err(_) = result {
-                    warn!("Something has gone wrong saving the level");
+                match result {
+                    Ok(_) => {
+                        remove_recovery_file();
+                        let _ = world.run_system_once(save_indicator::set_file_saved);
+                        world.insert_resource(LevelSource::File(WatchedFile::new(picked_file)));
+                    }
+                    Err(_) => {
+                        warn!("Something has gone wrong saving the level");
+                        ShowToast::new(ToastSeverity::Error, "Couldn't save the file").apply(world);
+                    }
                 };
 
                 world.entity_mut(trigger.target()).despawn();
@@ -54,6 +147,7 @@ impl Command for SaveLevelWithFileDialog {
     }
 }
 
+#[cfg(feature = "native-dialogs")]
 fn poll_filepicker_completion(
     mut tasks: Query<(Entity, &mut PickingFile)>,
     mut commands: Commands,
@@ -72,33 +166,24 @@ fn poll_filepicker_completion(
 pub struct LoadLevelWithFileDialog;
 impl Command for LoadLevelWithFileDialog {
     fn apply(self, world: &mut World) {
-        let thread_pool = AsyncComputeTaskPool::get();
-        let task = thread_pool.spawn(async move {
-            FileDialog::new()
-                .add_filter("JSON", &["level.json"])
-                .pick_file()
-        });
-
-        world.spawn(PickingFile(task)).observe(
+        pick_file(world, FileKind::Level, FileAction::Open).observe(
             |trigger: Trigger<FinishedPickingFile>, world: &mut World| {
-                let mut level_res = world.resource_mut::<LevelMemory>();
-                let file = &trigger.0;
-
-                if let Ok(file) = std::fs::read(file) {
-                    let level_from_file =
-                        serde_json::from_str::<Level>(&String::from_utf8(file).unwrap());
-
-                    if let Ok(level_from_file) = level_from_file {
-                        level_res.0 = level_from_file;
-                        let _ = world.run_system_once(delete_all::<LevelEntity>);
-                        let _ = world.run_system_once(clear_deckbar);
-                        let _ = world.run_system_once(spawn_entities_from_level_memory);
+                match read_level_file(&trigger.0) {
+                    Ok(loaded) => {
+                        if let Some(from_version) = loaded.migrated_from {
+                            world.insert_resource(ResaveOffer {
+                                path: trigger.0.clone(),
+                                level: loaded.level.clone(),
+                                from_version,
+                            });
+                        }
+
+                        ReplaceLevel(loaded.level).apply(world);
                         let _ = world.run_system_once(save_indicator::set_saved);
-                    } else {
-                        warn!("Couldn't load level from file");
+                        let _ = world.run_system_once(save_indicator::set_file_saved);
+                        world.insert_resource(LevelSource::File(WatchedFile::new(&trigger.0)));
                     }
-                } else {
-                    warn!("Couldn't read file when loading level");
+                    Err(err) => ShowLoadError(err).apply(world),
                 }
 
                 world.entity_mut(trigger.target()).despawn();
@@ -110,15 +195,7 @@ impl Command for LoadLevelWithFileDialog {
 pub struct SaveCardConstsWithFileDialog;
 impl Command for SaveCardConstsWithFileDialog {
     fn apply(self, world: &mut World) {
-        let thread_pool = AsyncComputeTaskPool::get();
-        let task = thread_pool.spawn(async move {
-            FileDialog::new()
-                .set_file_name("placeholder.consts.json")
-                .add_filter("JSON", &["consts.json"])
-                .save_file()
-        });
-
-        world.spawn(PickingFile(task)).observe(
+        pick_file(world, FileKind::CardConsts, FileAction::Save).observe(
             |trigger: Trigger<FinishedPickingFile>, world: &mut World| {
                 let card_consts = world.resource::<CardConsts>();
                 let picked_file = &trigger.0;
@@ -128,8 +205,14 @@ impl Command for SaveCardConstsWithFileDialog {
                     serde_json::to_string_pretty(&card_consts).unwrap(),
                 );
 
-                if let Err(_) = result {
-                    warn!("Something has gone wrong saving the level");
+                match result {
+                    Ok(_) => {
+                        world.insert_resource(WatchedConstsFile(WatchedFile::new(picked_file)))
+                    }
+                    Err(_) => {
+                        warn!("Something has gone wrong saving the level");
+                        ShowToast::new(ToastSeverity::Error, "Couldn't save the file").apply(world);
+                    }
                 };
 
                 world.entity_mut(trigger.target()).despawn();
@@ -141,33 +224,98 @@ impl Command for SaveCardConstsWithFileDialog {
 pub struct LoadCardConstsWithFileDialog;
 impl Command for LoadCardConstsWithFileDialog {
     fn apply(self, world: &mut World) {
-        let thread_pool = AsyncComputeTaskPool::get();
-        let task = thread_pool.spawn(async move {
-            FileDialog::new()
-                .add_filter("JSON", &["consts.json"])
-                .pick_file()
-        });
-
-        world.spawn(PickingFile(task)).observe(
+        pick_file(world, FileKind::CardConsts, FileAction::Open).observe(
             |trigger: Trigger<FinishedPickingFile>, world: &mut World| {
-                let mut card_consts = world.resource_mut::<CardConsts>();
-                let file = &trigger.0;
+                match read_json_file::<CardConsts>(&trigger.0) {
+                    Ok(card_consts_from_file) => {
+                        *world.resource_mut::<CardConsts>() = card_consts_from_file;
+                        world.insert_resource(WatchedConstsFile(WatchedFile::new(&trigger.0)));
+                    }
+                    Err(err) => ShowLoadError(err).apply(world),
+                }
+
+                world.entity_mut(trigger.target()).despawn();
+            },
+        );
+    }
+}
 
-                if let Ok(file) = std::fs::read(file) {
-                    let card_consts_from_file =
-                        serde_json::from_str::<CardConsts>(&String::from_utf8(file).unwrap());
+pub struct SaveCombatLogWithFileDialog;
+impl Command for SaveCombatLogWithFileDialog {
+    fn apply(self, world: &mut World) {
+        pick_file(world, FileKind::CombatLog, FileAction::Save).observe(
+            |trigger: Trigger<FinishedPickingFile>, world: &mut World| {
+                let combat_log = world.resource::<CombatLog>().to_json();
 
-                    if let Ok(card_consts_from_file) = card_consts_from_file {
-                        *card_consts = card_consts_from_file;
-                    } else {
-                        warn!("Couldn't load level from file");
+                match std::fs::write(&trigger.0, combat_log) {
+                    Ok(_) => {
+                        ShowToast::new(ToastSeverity::Success, "Combat log saved").apply(world)
                     }
-                } else {
-                    warn!("Couldn't read file when loading level");
-                }
+                    Err(_) => {
+                        warn!("Something has gone wrong saving the combat log");
+                        ShowToast::new(ToastSeverity::Error, "Couldn't save the combat log")
+                            .apply(world);
+                    }
+                };
 
                 world.entity_mut(trigger.target()).despawn();
             },
         );
     }
 }
+
+fn show_resave_offer(
+    mut contexts: EguiContexts,
+    resave_offer: Res<ResaveOffer>,
+    mut commands: Commands,
+) {
+    let file_name = resave_offer
+        .path
+        .file_name()
+        .map_or(String::new(), |name| name.to_string_lossy().to_string());
+
+    egui::Window::new("Upgrade level file?")
+        .collapsible(false)
+        .resizable(false)
+        .show(contexts.ctx_mut(), |ui| {
+            ui.label(format!(
+                "{file_name} was made with level version {} and was upgraded to version \
+                 {CURRENT_LEVEL_VERSION} when loading.",
+                resave_offer.from_version
+            ));
+            ui.label("Re-save it so it doesn't need upgrading again?");
+
+            ui.horizontal(|ui| {
+                if ui.button("Re-save").clicked() {
+                    let result = std::fs::write(
+                        &resave_offer.path,
+                        serde_json::to_string_pretty(&resave_offer.level).unwrap(),
+                    );
+
+                    match result {
+                        // Re-saving isn't an edit, so it shouldn't trigger a hot reload
+                        Ok(_) => commands.insert_resource(LevelSource::File(WatchedFile::new(
+                            &resave_offer.path,
+                        ))),
+                        Err(_) => {
+                            warn!("Something has gone wrong saving the level");
+                            commands.queue(ShowToast::new(
+                                ToastSeverity::Error,
+                                "Couldn't re-save the level",
+                            ));
+                        }
+                    };
+
+                    commands.remove_resource::<ResaveOffer>();
+                }
+
+                if ui.button("Not now").clicked() {
+                    commands.remove_resource::<ResaveOffer>();
+                }
+            });
+        });
+}
+
+fn withdraw_resave_offer(mut commands: Commands) {
+    commands.remove_resource::<ResaveOffer>();
+}
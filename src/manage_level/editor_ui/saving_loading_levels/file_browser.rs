@@ -0,0 +1,316 @@
+use bevy::prelude::*;
+use bevy_egui::{
+    egui::{self, Color32, ComboBox, ScrollArea},
+    EguiContextPass, EguiContexts,
+};
+use std::path::{Path, PathBuf};
+
+use crate::global::user_data_dir;
+
+use super::{FileAction, FileKind, FinishedPickingFile};
+
+/// Shows the file browser until a file is picked or it's cancelled
+#[derive(Component)]
+pub struct FileBrowserRequest {
+    pub kind: FileKind,
+    pub action: FileAction,
+}
+
+/// Directories the file browser can look through
+#[derive(Resource, Deref, DerefMut)]
+pub struct FileBrowserDirs(pub Vec<PathBuf>);
+
+impl Default for FileBrowserDirs {
+    fn default() -> Self {
+        let mut dirs = vec![PathBuf::from("assets/levels")];
+        if let Some(user_data_dir) = user_data_dir() {
+            dirs.push(user_data_dir);
+        }
+        FileBrowserDirs(dirs)
+    }
+}
+
+#[derive(Resource, Default)]
+struct FileBrowserState {
+    request: Option<Entity>,
+    current_dir: usize,
+    files: Vec<PathBuf>,
+    file_name: String,
+    new_dir: String,
+    renaming: Option<PathBuf>,
+    rename_to: String,
+    confirm_overwrite: Option<PathBuf>,
+    error: Option<String>,
+}
+
+pub fn file_browser_plugin(app: &mut App) {
+    app.add_systems(EguiContextPass, show_file_browser)
+        .init_resource::<FileBrowserDirs>()
+        .init_resource::<FileBrowserState>();
+}
+
+fn show_file_browser(
+    mut contexts: EguiContexts,
+    requests: Query<(Entity, &FileBrowserRequest)>,
+    mut dirs: ResMut<FileBrowserDirs>,
+    mut state: ResMut<FileBrowserState>,
+    mut commands: Commands,
+) {
+    let Some((request_e, request)) = requests.iter().next() else {
+        state.request = None;
+        return;
+    };
+
+    if state.request != Some(request_e) {
+        *state = FileBrowserState {
+            request: Some(request_e),
+            current_dir: state.current_dir.min(dirs.len().saturating_sub(1)),
+            file_name: match request.action {
+                FileAction::Save => request.kind.placeholder_file_name(),
+                FileAction::Open => String::new(),
+            },
+            ..default()
+        };
+        state.refresh(&dirs, request.kind);
+    }
+
+    let title = match request.action {
+        FileAction::Save => format!("Save {}", request.kind.to_string()),
+        FileAction::Open => format!("Open {}", request.kind.to_string()),
+    };
+
+    let mut picked: Option<PathBuf> = None;
+    let mut cancelled = false;
+
+    egui::Window::new(title)
+        .collapsible(false)
+        .anchor(egui::Align2::CENTER_CENTER, (0., 0.))
+        .show(contexts.ctx_mut(), |ui| {
+            ui.horizontal(|ui| {
+                let current_dir_name = dirs
+                    .get(state.current_dir)
+                    .map_or(String::new(), |dir| dir.display().to_string());
+
+                let mut current_dir = state.current_dir;
+                ComboBox::from_label("Directory")
+                    .selected_text(current_dir_name)
+                    .show_ui(ui, |ui| {
+                        for (i, dir) in dirs.iter().enumerate() {
+                            ui.selectable_value(&mut current_dir, i, dir.display().to_string());
+                        }
+                    });
+
+                if current_dir != state.current_dir || ui.button("Refresh").clicked() {
+                    state.current_dir = current_dir;
+                    state.refresh(&dirs, request.kind);
+                }
+            });
+
+            ui.collapsing("Directories", |ui| {
+                let mut to_remove: Option<usize> = None;
+                for (i, dir) in dirs.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.label(dir.display().to_string());
+                        if dirs.len() > 1 && ui.small_button("Remove").clicked() {
+                            to_remove = Some(i);
+                        }
+                    });
+                }
+
+                if let Some(to_remove) = to_remove {
+                    dirs.remove(to_remove);
+                    state.current_dir = 0;
+                    state.refresh(&dirs, request.kind);
+                }
+
+                ui.horizontal(|ui| {
+                    ui.text_edit_singleline(&mut state.new_dir);
+                    if ui.button("Add directory").clicked() && !state.new_dir.trim().is_empty() {
+                        dirs.push(PathBuf::from(state.new_dir.trim()));
+                        state.new_dir.clear();
+                        state.current_dir = dirs.len() - 1;
+                        state.refresh(&dirs, request.kind);
+                    }
+                });
+            });
+
+            ui.separator();
+
+            ScrollArea::vertical().max_height(200.).show(ui, |ui| {
+                if state.files.is_empty() {
+                    ui.label(format!("No .{} files here", request.kind.extension()));
+                }
+
+                for file in state.files.clone() {
+                    let file_name = file_name(&file);
+                    let is_selected = state.file_name == file_name;
+                    if ui.selectable_label(is_selected, &file_name).clicked() {
+                        state.file_name = file_name;
+                        state.renaming = None;
+                    }
+                }
+            });
+
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                ui.label("File name");
+                ui.text_edit_singleline(&mut state.file_name);
+            });
+
+            if let Some(renaming) = state.renaming.clone() {
+                ui.horizontal(|ui| {
+                    ui.label(format!("Rename {} to", file_name(&renaming)));
+                    ui.text_edit_singleline(&mut state.rename_to);
+                    let can_rename = is_valid_file_name(&state.rename_to, request.kind);
+                    if ui
+                        .add_enabled(can_rename, egui::Button::new("Confirm"))
+                        .clicked()
+                    {
+                        let rename_to =
+                            renaming.with_file_name(with_extension(&state.rename_to, request.kind));
+                        state.error = if rename_to.exists() {
+                            Some(format!("{} already exists", file_name(&rename_to)))
+                        } else {
+                            std::fs::rename(&renaming, &rename_to)
+                                .err()
+                                .map(|err| format!("Couldn't rename file: {err}"))
+                        };
+                        state.renaming = None;
+                        state.refresh(&dirs, request.kind);
+                    }
+                });
+            }
+
+            if let Some(confirm_overwrite) = state.confirm_overwrite.clone() {
+                ui.colored_label(
+                    Color32::YELLOW,
+                    format!(
+                        "{} already exists. Overwrite it?",
+                        file_name(&confirm_overwrite)
+                    ),
+                );
+                ui.horizontal(|ui| {
+                    if ui.button("Overwrite").clicked() {
+                        picked = Some(confirm_overwrite);
+                    }
+                    if ui.button("Keep it").clicked() {
+                        state.confirm_overwrite = None;
+                    }
+                });
+            } else {
+                ui.horizontal(|ui| {
+                    let confirm_text = match request.action {
+                        FileAction::Save => "Save",
+                        FileAction::Open => "Open",
+                    };
+
+                    let has_file_name = is_valid_file_name(&state.file_name, request.kind);
+                    if ui
+                        .add_enabled(has_file_name, egui::Button::new(confirm_text))
+                        .clicked()
+                    {
+                        if let Some(dir) = dirs.get(state.current_dir) {
+                            let path = dir.join(with_extension(&state.file_name, request.kind));
+                            match request.action {
+                                FileAction::Save if path.exists() => {
+                                    state.confirm_overwrite = Some(path);
+                                }
+                                FileAction::Save => match std::fs::create_dir_all(dir) {
+                                    Ok(_) => picked = Some(path),
+                                    Err(err) => {
+                                        state.error =
+                                            Some(format!("Couldn't create directory: {err}"));
+                                    }
+                                },
+                                FileAction::Open if !path.is_file() => {
+                                    state.error =
+                                        Some(format!("{} doesn't exist", file_name(&path)));
+                                }
+                                FileAction::Open => picked = Some(path),
+                            }
+                        }
+                    }
+
+                    if ui
+                        .add_enabled(has_file_name, egui::Button::new("Rename"))
+                        .clicked()
+                    {
+                        if let Some(dir) = dirs.get(state.current_dir) {
+                            let path = dir.join(with_extension(&state.file_name, request.kind));
+                            if path.is_file() {
+                                state.rename_to = state.file_name.clone();
+                                state.renaming = Some(path);
+                            } else {
+                                state.error = Some(format!("{} doesn't exist", file_name(&path)));
+                            }
+                        }
+                    }
+
+                    if ui.button("Cancel").clicked() {
+                        cancelled = true;
+                    }
+                });
+            }
+
+            if let Some(error) = &state.error {
+                ui.colored_label(Color32::RED, error);
+            }
+        });
+
+    if let Some(picked) = picked {
+        commands
+            .entity(request_e)
+            .remove::<FileBrowserRequest>()
+            .trigger(FinishedPickingFile(picked));
+    } else if cancelled {
+        commands.entity(request_e).despawn();
+    }
+}
+
+impl FileBrowserState {
+    fn refresh(&mut self, dirs: &FileBrowserDirs, kind: FileKind) {
+        self.files.clear();
+
+        let Some(dir) = dirs.get(self.current_dir) else {
+            return;
+        };
+
+        // A directory that doesn't exist yet is fine, it gets created when saving into it
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return;
+        };
+
+        let extension = format!(".{}", kind.extension());
+        self.files = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file() && file_name(path).ends_with(&extension))
+            .collect();
+        self.files.sort();
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map_or(String::new(), |name| name.to_string_lossy().to_string())
+}
+
+/// Names are joined onto the current folder, so they can't be empty or lead out of it
+fn is_valid_file_name(file_name: &str, kind: FileKind) -> bool {
+    let file_name = file_name.trim();
+    let stem = file_name
+        .strip_suffix(&format!(".{}", kind.extension()))
+        .unwrap_or(file_name);
+    !stem.is_empty() && !stem.contains(['/', '\\']) && stem != "." && stem != ".."
+}
+
+fn with_extension(file_name: &str, kind: FileKind) -> String {
+    let file_name = file_name.trim();
+    let extension = format!(".{}", kind.extension());
+    if file_name.ends_with(&extension) {
+        file_name.to_string()
+    } else {
+        format!("{file_name}{extension}")
+    }
+}
@@ -0,0 +1,155 @@
+use bevy::{ecs::system::RunSystemOnce, prelude::*};
+use bevy_egui::egui::{self, Ui};
+use std::time::{Duration, SystemTime};
+
+use crate::{
+    global::GameState,
+    manage_level::{Level, Pause, ReplaceLevel},
+};
+
+use super::save_indicator;
+
+/// A named copy of the level, like the quicksave but there can be as many as needed
+pub struct Snapshot {
+    name: String,
+    taken_at: SystemTime,
+    level: Level,
+}
+
+#[derive(Resource, Default)]
+pub struct Snapshots {
+    slots: Vec<Snapshot>,
+    new_slot_name: String,
+    renaming: Option<usize>,
+    diff: Option<(usize, Vec<String>)>,
+}
+
+pub fn snapshots_plugin(app: &mut App) {
+    app.add_systems(OnExit(GameState::InGame), clear_snapshots)
+        .init_resource::<Snapshots>();
+}
+
+/// The arena no longer matches the level file afterwards, so it counts as an edit
+fn load_snapshot(world: &mut World, level: Level) {
+    ReplaceLevel(level).apply(world);
+    let _ = world.run_system_once(save_indicator::set_not_saved);
+}
+
+pub fn snapshots_ui(ui: &mut Ui, snapshots: &mut Snapshots, commands: &mut Commands) {
+    ui.horizontal(|ui| {
+        ui.text_edit_singleline(&mut snapshots.new_slot_name);
+        if ui.button("Take snapshot").clicked() {
+            let name = if snapshots.new_slot_name.trim().is_empty() {
+                format!("Snapshot {}", snapshots.slots.len() + 1)
+            } else {
+                std::mem::take(&mut snapshots.new_slot_name)
+            };
+
+            commands.queue(move |world: &mut World| {
+                let level = Level::get_current(world);
+                world.resource_mut::<Snapshots>().slots.push(Snapshot {
+                    name,
+                    taken_at: SystemTime::now(),
+                    level,
+                });
+            });
+        }
+    });
+
+    let mut to_delete: Option<usize> = None;
+    for (i, slot) in snapshots.slots.iter_mut().enumerate() {
+        ui.separator();
+
+        if snapshots.renaming == Some(i) {
+            let rename_field = ui.text_edit_singleline(&mut slot.name);
+            if rename_field.lost_focus() {
+                snapshots.renaming = None;
+            }
+        } else {
+            ui.strong(&slot.name);
+        }
+
+        ui.label(format!(
+            "{} cards, taken {} ago",
+            slot.level.cards.len(),
+            format_age(slot.taken_at.elapsed().unwrap_or_default())
+        ));
+
+        ui.horizontal(|ui| {
+            if ui.button("Load").clicked() {
+                let level = slot.level.clone();
+                commands.queue(Pause);
+                commands.queue(move |world: &mut World| load_snapshot(world, level));
+            }
+
+            if ui.button("Rename").clicked() {
+                snapshots.renaming = Some(i);
+            }
+
+            if ui.button("Diff").clicked() {
+                let level = slot.level.clone();
+                commands.queue(move |world: &mut World| {
+                    let changes = level.diff(&Level::get_current(world));
+                    world.resource_mut::<Snapshots>().diff = Some((i, changes));
+                });
+            }
+
+            if ui.button("Delete").clicked() {
+                to_delete = Some(i);
+            }
+        });
+
+        if let Some((diff_slot, changes)) = &snapshots.diff {
+            if *diff_slot == i {
+                egui::Frame::group(ui.style()).show(ui, |ui| {
+                    ui.label("Changes since this snapshot:");
+                    if changes.is_empty() {
+                        ui.label("None");
+                    }
+                    for change in changes {
+                        ui.label(change);
+                    }
+                });
+            }
+        }
+    }
+
+    if let Some(to_delete) = to_delete {
+        snapshots.slots.remove(to_delete);
+        // Indices after the deleted slot have shifted
+        snapshots.renaming = None;
+        snapshots.diff = None;
+    }
+}
+
+fn format_age(age: Duration) -> String {
+    let secs = age.as_secs();
+    if secs < 60 {
+        format!("{secs}s")
+    } else {
+        format!("{}m {}s", secs / 60, secs % 60)
+    }
+}
+
+fn clear_snapshots(mut snapshots: ResMut<Snapshots>) {
+    *snapshots = Snapshots::default();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{card::CardConsts, manage_level::LevelMemory};
+    use save_indicator::{HasUnsavedEdits, SaveIndicator};
+
+    #[test]
+    fn loading_a_snapshot_leaves_unsaved_edits() {
+        let mut world = World::new();
+        world.init_resource::<CardConsts>();
+        world.init_resource::<HasUnsavedEdits>();
+        world.insert_resource(LevelMemory(Level::get_stub()));
+        world.spawn(SaveIndicator);
+
+        load_snapshot(&mut world, Level::get_stub());
+        assert!(**world.resource::<HasUnsavedEdits>());
+    }
+}
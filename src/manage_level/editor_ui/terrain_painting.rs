@@ -0,0 +1,78 @@
+use bevy::prelude::*;
+use bevy_egui::egui::Ui;
+
+use crate::{
+    deckbar::SelectedCard,
+    global::{in_editor, CursorWorldCoords, GameState, IsPointerOverUi},
+    manage_level::game_controls::allow_game_reset,
+    terrain::{Terrain, TerrainKind},
+};
+
+use super::save_indicator;
+
+/// What dragging the mouse over the arena paints, `None` is the eraser
+#[derive(Resource)]
+pub struct TerrainBrush {
+    pub is_painting: bool,
+    pub kind: Option<TerrainKind>,
+}
+
+impl Default for TerrainBrush {
+    fn default() -> Self {
+        TerrainBrush {
+            is_painting: false,
+            kind: Some(TerrainKind::Water),
+        }
+    }
+}
+
+pub fn terrain_painting_plugin(app: &mut App) {
+    app.add_systems(
+        FixedPreUpdate,
+        paint_terrain.run_if(in_state(GameState::InGame).and(in_editor)),
+    )
+    .add_systems(OnExit(GameState::InGame), put_brush_down)
+    .init_resource::<TerrainBrush>();
+}
+
+pub fn terrain_brush_ui(ui: &mut Ui, brush: &mut TerrainBrush) {
+    ui.checkbox(&mut brush.is_painting, "Paint terrain by dragging");
+
+    ui.horizontal(|ui| {
+        for kind in [TerrainKind::Water, TerrainKind::Bridge, TerrainKind::Wall] {
+            ui.selectable_value(&mut brush.kind, Some(kind), kind.to_string());
+        }
+        ui.selectable_value(&mut brush.kind, None, "Erase");
+    });
+}
+
+fn paint_terrain(
+    brush: Res<TerrainBrush>,
+    mut terrain: ResMut<Terrain>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    cursor_world_coords: Res<CursorWorldCoords>,
+    is_pointer_over_ui: Res<IsPointerOverUi>,
+    selected_card: Option<Single<Entity, With<SelectedCard>>>,
+    mut commands: Commands,
+) {
+    if !brush.is_painting
+        || !mouse.pressed(MouseButton::Left)
+        || **is_pointer_over_ui
+        || selected_card.is_some()
+    {
+        return;
+    }
+
+    // Avoid touching the resource when nothing changes so the tiles aren't redrawn every frame
+    if terrain.is_painted(**cursor_world_coords, brush.kind) {
+        return;
+    }
+
+    terrain.paint(**cursor_world_coords, brush.kind);
+    commands.run_system_cached(allow_game_reset);
+    commands.run_system_cached(save_indicator::set_not_saved);
+}
+
+fn put_brush_down(mut brush: ResMut<TerrainBrush>) {
+    brush.is_painting = false;
+}
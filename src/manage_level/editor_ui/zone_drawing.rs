@@ -0,0 +1,149 @@
+use bevy::{color::palettes::tailwind::GRAY_400, prelude::*};
+use bevy_egui::egui::{ComboBox, Ui};
+use strum::IntoEnumIterator;
+
+use crate::{
+    card::Card,
+    deckbar::SelectedCard,
+    global::{in_editor, CursorWorldCoords, GameState, IsPointerOverUi},
+    manage_level::game_controls::allow_game_reset,
+    placement::{outline_rect, PlacementZone, PlacementZones, PLACEMENT_ZONE_COLOR},
+};
+
+use super::save_indicator;
+
+/// Anything smaller is taken to be a click rather than a drag
+const MIN_ZONE_SIZE: f32 = 10.;
+
+/// Dragging over the arena draws a placement zone for `card`, right clicking one removes it
+#[derive(Resource)]
+pub struct ZoneBrush {
+    pub is_drawing: bool,
+    pub card: Card,
+}
+
+impl Default for ZoneBrush {
+    fn default() -> Self {
+        ZoneBrush {
+            is_drawing: false,
+            card: Card::Farmer,
+        }
+    }
+}
+
+pub fn zone_drawing_plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        draw_zones.run_if(in_state(GameState::InGame).and(in_editor)),
+    )
+    .add_systems(OnExit(GameState::InGame), put_brush_down)
+    .init_resource::<ZoneBrush>();
+}
+
+pub fn zone_brush_ui(
+    ui: &mut Ui,
+    brush: &mut ZoneBrush,
+    zones: &mut PlacementZones,
+    commands: &mut Commands,
+) {
+    ui.checkbox(&mut brush.is_drawing, "Draw placement zones by dragging");
+    ComboBox::from_label("Zone for")
+        .selected_text(brush.card.to_string())
+        .show_ui(ui, |ui| {
+            for card in Card::iter() {
+                ui.selectable_value(&mut brush.card, card, card.to_string());
+            }
+        });
+    ui.label("Cards without a zone can be placed anywhere");
+
+    let mut to_remove: Option<usize> = None;
+    for (i, zone) in zones.iter().enumerate() {
+        ui.horizontal(|ui| {
+            ui.label(format!(
+                "{} from ({:.0}, {:.0}) to ({:.0}, {:.0})",
+                zone.card.to_string(),
+                zone.rect.min.x,
+                zone.rect.min.y,
+                zone.rect.max.x,
+                zone.rect.max.y
+            ));
+            if ui.small_button("Remove").clicked() {
+                to_remove = Some(i);
+            }
+        });
+    }
+    if let Some(to_remove) = to_remove {
+        zones.remove(to_remove);
+        commands.run_system_cached(allow_game_reset);
+        commands.run_system_cached(save_indicator::set_not_saved);
+    }
+}
+
+fn draw_zones(
+    brush: Res<ZoneBrush>,
+    mut zones: ResMut<PlacementZones>,
+    mut drag_start: Local<Option<Vec2>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    cursor_world_coords: Res<CursorWorldCoords>,
+    is_pointer_over_ui: Res<IsPointerOverUi>,
+    selected_card: Option<Single<Entity, With<SelectedCard>>>,
+    mut draw: Gizmos,
+    mut commands: Commands,
+) {
+    if !brush.is_drawing || selected_card.is_some() {
+        *drag_start = None;
+        return;
+    }
+
+    // Every zone is shown while drawing, the brush card's ones stand out
+    for zone in zones.iter() {
+        if zone.card == brush.card {
+            outline_rect(&mut draw, zone.rect, PLACEMENT_ZONE_COLOR);
+        } else {
+            outline_rect(&mut draw, zone.rect, GRAY_400);
+        }
+    }
+
+    let cursor = **cursor_world_coords;
+    if !**is_pointer_over_ui {
+        if mouse.just_pressed(MouseButton::Left) {
+            *drag_start = Some(cursor);
+        }
+
+        if mouse.just_pressed(MouseButton::Right) {
+            let clicked = zones
+                .iter()
+                .rposition(|zone| zone.card == brush.card && zone.rect.contains(cursor));
+            if let Some(clicked) = clicked {
+                zones.remove(clicked);
+                commands.run_system_cached(allow_game_reset);
+                commands.run_system_cached(save_indicator::set_not_saved);
+            }
+        }
+    }
+
+    let Some(start) = *drag_start else {
+        return;
+    };
+    let rect = Rect::from_corners(start, cursor);
+    outline_rect(&mut draw, rect, Color::WHITE);
+
+    if !mouse.just_released(MouseButton::Left) {
+        return;
+    }
+    *drag_start = None;
+
+    if rect.width() < MIN_ZONE_SIZE || rect.height() < MIN_ZONE_SIZE {
+        return;
+    }
+    zones.push(PlacementZone {
+        card: brush.card,
+        rect,
+    });
+    commands.run_system_cached(allow_game_reset);
+    commands.run_system_cached(save_indicator::set_not_saved);
+}
+
+fn put_brush_down(mut brush: ResMut<ZoneBrush>) {
+    brush.is_drawing = false;
+}
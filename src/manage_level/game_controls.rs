@@ -1,48 +1,58 @@
+pub mod pause_menu;
 pub mod save_indicator;
+pub mod time_controls;
 
-use super::game_messages::SetMessage;
+use super::game_messages::{SetControlsHint, SetMessage};
 use super::{
     pause, reset_level_progress, save_level_to_memory, set_message,
     spawn_entities_from_level_memory, toggle_pause, IsPaused, LevelEntity, LevelMemory,
 };
-use crate::card::{MaybeCard, SpawnCard};
+use crate::card::{Card, MaybeCard, SpawnCard};
 use crate::debug_ui::DisplayInDebug;
 use crate::deckbar::{
-    deselect_card, select_card, DeleteSelectedCard, InitializeDeckbar, SelectedCard,
-};
-use crate::global::{
-    get_left_river_rect, get_middle_river_rect, get_right_river_rect, in_editor, not_in_editor,
-    CursorWorldCoords, GameState, IsPointerOverUi,
+    deselect_card, select_card, DeckBarRoot, DeleteSelectedCard, DraggedCard, InitializeDeckbar,
+    SelectedCard,
 };
+use crate::global::{in_editor, not_in_editor, GameState};
 use crate::manage_level::InEditorRes;
-use crate::manage_level::{unpause, Level};
+use crate::manage_level::{unpause, Level, LevelProgress, LevelStats, Pause};
+use crate::placement::Placement;
+use pause_menu::PauseMenuOpen;
+
+use super::editor_ui::{TerrainBrush, ZoneBrush};
 use bevy::ecs::schedule::ScheduleConfigs;
-use bevy::ecs::system::ScheduleSystem;
+use bevy::ecs::system::{RunSystemOnce, ScheduleSystem};
 use bevy::input::common_conditions::input_just_pressed;
 use bevy::input::mouse::MouseButtonInput;
 use bevy::input::ButtonState;
 use bevy::prelude::*;
+use bevy::ui::RelativeCursorPosition;
 use bevy_egui::input::egui_wants_any_keyboard_input;
 
-pub const CONTROLS_MESSAGE: &'static str = "[Space] to start level\n[Z] to restart level\n";
-pub const CONTROLS_EDITOR_MESSAGE: &'static str =
-    "[Space] to toggle pausing \n[Click] on spawned cards to delete\n";
-
 #[derive(Resource, Deref, DerefMut, Default, PartialEq)]
 pub(crate) struct GameIsReset(bool);
 
+/// How many of the level's hints the player has asked for
+#[derive(Resource, Deref, DerefMut, Default)]
+struct RevealedHints(usize);
+
 /// Handles all controls for the game
 pub fn game_controls_plugin(app: &mut App) {
     app.add_plugins(save_indicator::save_indicator_plugin)
+        .add_plugins(time_controls::time_controls_plugin)
+        .add_plugins(pause_menu::pause_menu_plugin)
         .add_systems(
             Startup,
-            remove_card_on_right_click_in_editor.after(InitializeDeckbar),
+            (
+                remove_card_on_right_click_in_editor,
+                drag_cards_from_deckbar,
+            )
+                .after(InitializeDeckbar),
         )
         .add_systems(
             OnEnter(GameState::InGame),
             (
-                set_starting_message.run_if(not_in_editor),
-                set_message(CONTROLS_EDITOR_MESSAGE).run_if(in_editor),
+                (reset_revealed_hints, set_starting_message).chain(),
                 disallow_game_reset,
                 save_indicator::set_saved,
             ),
@@ -51,17 +61,21 @@ pub fn game_controls_plugin(app: &mut App) {
             OnEnter(IsPaused::False),
             (
                 allow_game_reset,
-                save_indicator::set_not_saved,
+                save_indicator::show_not_saved,
                 set_message(""),
             ),
         )
         .add_systems(
             OnEnter(IsPaused::True),
-            (
-                set_starting_message.run_if(in_editor),
-                set_message(CONTROLS_EDITOR_MESSAGE).run_if(in_editor),
-            ),
+            set_starting_message.run_if(in_editor),
         )
+        .add_systems(
+            Update,
+            update_controls_hint.run_if(in_state(GameState::InGame)),
+        )
+        .add_systems(OnExit(GameState::InGame), |mut commands: Commands| {
+            commands.queue(SetControlsHint(String::new()));
+        })
         .add_systems(
             FixedPreUpdate,
             (
@@ -71,6 +85,9 @@ pub fn game_controls_plugin(app: &mut App) {
                     restart_level().run_if(
                         input_just_pressed(KeyCode::KeyZ).and(resource_equals(GameIsReset(false))),
                     ),
+                    (reveal_hint, set_starting_message)
+                        .chain()
+                        .run_if(input_just_pressed(KeyCode::KeyH).and(in_state(IsPaused::True))),
                 )
                     .run_if(not_in_editor),
                 (
@@ -88,15 +105,15 @@ pub fn game_controls_plugin(app: &mut App) {
                     select_card(1).run_if(input_just_pressed(KeyCode::Digit2)),
                     select_card(2).run_if(input_just_pressed(KeyCode::Digit3)),
                     select_card(3).run_if(input_just_pressed(KeyCode::Digit4)),
-                    deselect_card.run_if(input_just_pressed(KeyCode::Escape)),
                     deselect_card.run_if(input_just_pressed(KeyCode::CapsLock)),
                 )
                     .run_if(not(egui_wants_any_keyboard_input)),
                 spawn_card_on_click,
             )
-                .run_if(in_state(GameState::InGame)),
+                .run_if(in_state(GameState::InGame).and(not(resource_exists::<PauseMenuOpen>))),
         )
-        .init_resource::<GameIsReset>();
+        .init_resource::<GameIsReset>()
+        .init_resource::<RevealedHints>();
 
     if crate::debug::in_debug() {
         app.add_systems(FixedUpdate, display_game_is_reset);
@@ -114,29 +131,116 @@ fn restart_level() -> ScheduleConfigs<ScheduleSystem> {
         .chain()
 }
 
-fn set_starting_message(mut commands: Commands, level: Res<LevelMemory>) {
-    let condition_string = get_condition_string(&**level);
-    let starting_message = format!("{CONTROLS_MESSAGE}{condition_string}");
+fn reset_revealed_hints(mut revealed_hints: ResMut<RevealedHints>) {
+    **revealed_hints = 0;
+}
+
+fn reveal_hint(mut revealed_hints: ResMut<RevealedHints>, level: Res<LevelMemory>) {
+    if **revealed_hints < level.metadata.hints.len() {
+        **revealed_hints += 1;
+    }
+}
+
+/// Same as pressing [Z]
+pub struct RestartLevel;
+impl Command for RestartLevel {
+    fn apply(self, world: &mut World) {
+        let _ = world.run_system_once(spawn_entities_from_level_memory);
+        Pause.apply(world);
+        let _ = world.run_system_once(reset_level_progress);
+        let _ = world.run_system_once(disallow_game_reset);
+        let _ = world.run_system_once(save_indicator::set_saved);
+    }
+}
+
+fn set_starting_message(
+    mut commands: Commands,
+    level: Res<LevelMemory>,
+    revealed_hints: Res<RevealedHints>,
+) {
+    let metadata = &level.metadata;
+    let mut starting_message = String::new();
+
+    if !metadata.name.is_empty() {
+        starting_message += &format!("{}\n", metadata.name);
+    }
+    if !metadata.description.is_empty() {
+        starting_message += &format!("{}\n\n", metadata.description);
+    }
+
+    starting_message += &get_condition_string(&**level);
+
+    if let Some(par) = metadata.par {
+        starting_message += &format!("\nPar: {}", par.to_string());
+    }
+
+    for hint in metadata.hints.iter().take(**revealed_hints) {
+        starting_message += &format!("\nHint: {hint}");
+    }
 
     commands.queue(SetMessage(starting_message));
+}
+
+/// Lists the keys that do something in the current state, so the list never goes stale
+fn update_controls_hint(
+    in_editor: Res<InEditorRes>,
+    is_paused: Res<State<IsPaused>>,
+    level_progress: Res<State<LevelProgress>>,
+    game_is_reset: Res<GameIsReset>,
+    revealed_hints: Res<RevealedHints>,
+    level: Res<LevelMemory>,
+    pause_menu_open: Option<Res<PauseMenuOpen>>,
+    mut commands: Commands,
+) {
+    let paused = **is_paused == IsPaused::True;
+    let playing = **level_progress == LevelProgress::Null;
+    let mut controls: Vec<&str> = Vec::new();
 
-    pub fn get_condition_string(level: &Level) -> String {
-        let mut win_condition_card = level.win_condition.card.to_string();
-        let win_condition_count = level.win_condition.count_dead.to_string();
-        if level.win_condition.count_dead > 1 {
-            win_condition_card = format!("{win_condition_card}s");
+    if pause_menu_open.is_some() {
+        controls.push("[Esc] to resume");
+    } else if **in_editor {
+        controls.push("[Space] to toggle pausing");
+        if !**game_is_reset {
+            controls.push("[Z] to restart level");
+        }
+        controls.push("[X] to save to memory");
+        controls.push("[Click] on spawned cards to delete");
+    } else {
+        if paused && playing {
+            controls.push("[Space] to start level");
+        }
+        if !**game_is_reset {
+            controls.push("[Z] to restart level");
+        }
+        if paused && **revealed_hints < level.metadata.hints.len() {
+            controls.push("[H] for a hint");
         }
+        if playing {
+            controls.push("[1-4] to pick a card");
+            controls.push("[Drag] a card onto the arena to place it");
+            controls.push("[Esc] for the pause menu");
+        }
+    }
 
-        let lose_condition_card = level.lose_condition.card.to_string();
-        let lose_condition_count = level.lose_condition.count_dead.to_string();
-        let s = if level.lose_condition.count_dead > 1 {
-            String::from("s")
-        } else {
-            String::from("")
-        };
+    commands.queue(SetControlsHint(controls.join("\n")));
+}
 
-        format!("Eliminate {win_condition_count} {win_condition_card} and avoid {lose_condition_count} {lose_condition_card} death{s}")
+fn get_condition_string(level: &Level) -> String {
+    let mut win_condition_card = level.win_condition.card.to_string();
+    let win_condition_count = level.win_condition.count_dead.to_string();
+    if level.win_condition.count_dead > 1 {
+        win_condition_card = format!("{win_condition_card}s");
     }
+
+    let lose_condition_card = level.lose_condition.card.to_string();
+    let lose_condition_count = level.lose_condition.count_dead.to_string();
+    let s = if level.lose_condition.count_dead > 1 {
+        String::from("s")
+    } else {
+        String::from("")
+    };
+
+    format!("Eliminate {win_condition_count} {win_condition_card} and avoid {lose_condition_count} {lose_condition_card} death{s}")
 }
 
 fn delete_level_entities_on_click(
@@ -148,7 +252,13 @@ fn delete_level_entities_on_click(
         commands.entity(level_entity).observe(
             |trigger: Trigger<Pointer<Click>>,
              is_paused: Res<State<IsPaused>>,
+             terrain_brush: Res<TerrainBrush>,
+             zone_brush: Res<ZoneBrush>,
              mut commands: Commands| {
+                if terrain_brush.is_painting || zone_brush.is_drawing {
+                    return;
+                }
+
                 match **is_paused {
                     IsPaused::True => {
                         commands.entity(trigger.target()).despawn();
@@ -166,9 +276,9 @@ fn delete_level_entities_on_click(
 fn spawn_card_on_click(
     mut commands: Commands,
     mut mousebtn_evr: EventReader<MouseButtonInput>,
-    mouse_coords: Res<CursorWorldCoords>,
-    is_pointer_over_ui: Res<IsPointerOverUi>,
+    placement: Placement,
     selected_card: Option<Single<&MaybeCard, With<SelectedCard>>>,
+    mut level_stats: ResMut<LevelStats>,
 ) {
     let Some(selected_card) = selected_card.map(Single::into_inner) else {
         mousebtn_evr.clear();
@@ -180,24 +290,116 @@ fn spawn_card_on_click(
     };
 
     for ev in mousebtn_evr.read() {
-        if ev.state != ButtonState::Pressed
-            || **is_pointer_over_ui
-            || (!selected_card.is_placeable_over_water() && !in_bounds(**mouse_coords))
-        {
+        if ev.state != ButtonState::Pressed || !placement.can_place(selected_card) {
             continue;
         }
 
-        commands.queue(SpawnCard::new(selected_card, mouse_coords.0));
-        commands.queue(DeleteSelectedCard::default());
-        commands.run_system_cached(allow_game_reset);
-        commands.run_system_cached(save_indicator::set_not_saved);
+        deploy_selected_card(
+            selected_card,
+            placement.cursor(),
+            &mut level_stats,
+            &mut commands,
+        );
+    }
+}
+
+/// Shared by clicking the arena and dropping a dragged card on it
+fn deploy_selected_card(
+    card: Card,
+    position: Vec2,
+    level_stats: &mut LevelStats,
+    commands: &mut Commands,
+) {
+    commands.queue(SpawnCard::new(card, position));
+    commands.queue(DeleteSelectedCard::default());
+    level_stats.card_placed(card);
+    commands.run_system_cached(allow_game_reset);
+    commands.run_system_cached(save_indicator::set_not_saved);
+}
+
+/// Pressing on a card and dragging it onto the arena places it where it's dropped. Dropping it
+/// back on the deckbar cancels
+fn drag_cards_from_deckbar(cards_q: Query<Entity, With<MaybeCard>>, mut commands: Commands) {
+    for card in cards_q {
+        commands
+            .entity(card)
+            .observe(start_card_drag)
+            .observe(track_card_drag)
+            .observe(end_card_drag);
     }
+}
+
+fn start_card_drag(
+    trigger: Trigger<Pointer<DragStart>>,
+    cards_q: Query<&MaybeCard>,
+    old_selected_card: Option<Single<Entity, With<SelectedCard>>>,
+    pause_menu_open: Option<Res<PauseMenuOpen>>,
+    mut commands: Commands,
+) {
+    if trigger.button != PointerButton::Primary || pause_menu_open.is_some() {
+        return;
+    }
+
+    let card_e = trigger.target();
+    if !cards_q.get(card_e).is_ok_and(|card| card.is_some()) {
+        return;
+    }
+
+    if let Some(old_selected_card) = old_selected_card {
+        if *old_selected_card != card_e {
+            commands
+                .entity(old_selected_card.into_inner())
+                .remove::<SelectedCard>();
+        }
+    }
+    commands.entity(card_e).insert(SelectedCard);
+    commands.insert_resource(DraggedCard {
+        slot: card_e,
+        has_left_deckbar: false,
+    });
+}
+
+fn track_card_drag(
+    _trigger: Trigger<Pointer<Drag>>,
+    dragged_card: Option<ResMut<DraggedCard>>,
+    deckbar: Single<&RelativeCursorPosition, With<DeckBarRoot>>,
+) {
+    if let Some(mut dragged_card) = dragged_card {
+        if !dragged_card.has_left_deckbar && !deckbar.mouse_over() {
+            dragged_card.has_left_deckbar = true;
+        }
+    }
+}
+
+fn end_card_drag(
+    trigger: Trigger<Pointer<DragEnd>>,
+    dragged_card: Option<Res<DraggedCard>>,
+    selected_card: Option<Single<&MaybeCard, With<SelectedCard>>>,
+    placement: Placement,
+    pause_menu_open: Option<Res<PauseMenuOpen>>,
+    mut level_stats: ResMut<LevelStats>,
+    mut commands: Commands,
+) {
+    let Some(dragged_card) = dragged_card.filter(|_| trigger.button == PointerButton::Primary)
+    else {
+        return;
+    };
+    commands.remove_resource::<DraggedCard>();
+
+    if pause_menu_open.is_some() {
+        return;
+    }
+
+    let Some(card) = selected_card.and_then(|selected_card| selected_card.0) else {
+        return;
+    };
 
-    fn in_bounds(v: Vec2) -> bool {
-        !get_left_river_rect().contains(v)
-            && !get_middle_river_rect().contains(v)
-            && !get_right_river_rect().contains(v)
+    if placement.can_place(card) {
+        deploy_selected_card(card, placement.cursor(), &mut level_stats, &mut commands);
+    } else if dragged_card.has_left_deckbar && placement.is_pointer_over_ui() {
+        commands.run_system_cached(deselect_card);
     }
+    // Dropped somewhere it can't go, it stays selected so it can still be clicked down
 }
 
 fn remove_card_on_right_click_in_editor(
@@ -0,0 +1,195 @@
+use bevy::{
+    color::palettes::tailwind::*, input::common_conditions::input_just_pressed, prelude::*,
+};
+use bevy_egui::input::egui_wants_any_keyboard_input;
+use DuckSlayer::delete_all;
+
+use crate::{
+    card::MaybeCard,
+    deckbar::{deselect_card, SelectedCard},
+    global::GameState,
+    manage_level::{InEditorRes, IsPaused, LevelMemory, LevelProgress, Pause, RestartLevel},
+    settings_screen::ShowSettingsScreen,
+};
+
+use super::get_condition_string;
+
+/// Present while the pause menu is showing. Gameplay input is ignored until it's gone
+#[derive(Resource)]
+pub struct PauseMenuOpen {
+    /// The level was running when the menu was opened, so resuming should carry on running it
+    unpause_on_resume: bool,
+}
+
+#[derive(Component)]
+#[require(Name::new("Pause Menu"))]
+struct PauseMenu;
+
+pub fn pause_menu_plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            on_escape.run_if(
+                input_just_pressed(KeyCode::Escape).and(not(egui_wants_any_keyboard_input)),
+            ),
+            spawn_pause_menu.run_if(resource_added::<PauseMenuOpen>),
+        )
+            .chain()
+            .run_if(in_state(GameState::InGame)),
+    )
+    .add_systems(
+        PostUpdate,
+        delete_all::<PauseMenu>.run_if(resource_removed::<PauseMenuOpen>),
+    )
+    .add_systems(OnExit(GameState::InGame), |mut commands: Commands| {
+        commands.queue(ClosePauseMenu { resume: false });
+    });
+}
+
+/// Escape lets go of the selected card first, and only opens or closes the menu when there's
+/// nothing to let go of
+fn on_escape(
+    selected_card: Option<Single<&MaybeCard, With<SelectedCard>>>,
+    pause_menu_open: Option<Res<PauseMenuOpen>>,
+    in_editor: Res<InEditorRes>,
+    level_progress: Res<State<LevelProgress>>,
+    mut commands: Commands,
+) {
+    let card_selected = selected_card.is_some_and(|card| card.is_some());
+    if card_selected || **in_editor {
+        commands.run_system_cached(deselect_card);
+        return;
+    }
+
+    if pause_menu_open.is_some() {
+        commands.queue(ClosePauseMenu { resume: true });
+    } else if **level_progress == LevelProgress::Null {
+        commands.run_system_cached(deselect_card);
+        commands.queue(OpenPauseMenu);
+    }
+}
+
+struct OpenPauseMenu;
+impl Command for OpenPauseMenu {
+    fn apply(self, world: &mut World) {
+        let unpause_on_resume = **world.resource::<State<IsPaused>>() == IsPaused::False;
+        Pause.apply(world);
+        world.insert_resource(PauseMenuOpen { unpause_on_resume });
+    }
+}
+
+struct ClosePauseMenu {
+    resume: bool,
+}
+impl Command for ClosePauseMenu {
+    fn apply(self, world: &mut World) {
+        let Some(pause_menu_open) = world.remove_resource::<PauseMenuOpen>() else {
+            return;
+        };
+
+        if self.resume && pause_menu_open.unpause_on_resume {
+            world
+                .resource_mut::<NextState<IsPaused>>()
+                .set(IsPaused::False);
+        }
+    }
+}
+
+fn spawn_pause_menu(
+    level: Res<LevelMemory>,
+    asset_server: Res<AssetServer>,
+    mut commands: Commands,
+) {
+    let mut goals = String::new();
+    if !level.metadata.name.is_empty() {
+        goals += &format!("{}\n", level.metadata.name);
+    }
+    goals += &get_condition_string(&level);
+    if let Some(par) = level.metadata.par {
+        goals += &format!("\nPar: {}", par.to_string());
+    }
+
+    commands
+        .spawn((
+            PauseMenu,
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                ..default()
+            },
+            BackgroundColor(Color::BLACK.with_alpha(0.5)),
+            // Counts as the pointer being over ui, so clicks don't reach the arena
+            Interaction::default(),
+            GlobalZIndex(1),
+        ))
+        .with_children(|p| {
+            p.spawn((
+                Node {
+                    margin: UiRect::AUTO,
+                    padding: UiRect::all(Val::Px(30.)),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(12.),
+                    ..default()
+                },
+                BackgroundColor(CYAN_500.into()),
+                BorderRadius::all(Val::Px(25.)),
+            ))
+            .with_children(|p| {
+                p.spawn((
+                    Text::new("Paused"),
+                    TextFont {
+                        font: asset_server.load("DynaPuff-Regular.ttf"),
+                        font_size: 36.,
+                        ..default()
+                    },
+                    TextColor(Color::BLACK),
+                ));
+                p.spawn((
+                    Text::new(goals),
+                    TextLayout::new_with_justify(JustifyText::Center),
+                ));
+
+                menu_btn(p, "Resume").observe(
+                    |_: Trigger<Pointer<Click>>, mut commands: Commands| {
+                        commands.queue(ClosePauseMenu { resume: true });
+                    },
+                );
+                menu_btn(p, "Restart").observe(
+                    |_: Trigger<Pointer<Click>>, mut commands: Commands| {
+                        commands.queue(ClosePauseMenu { resume: false });
+                        commands.queue(RestartLevel);
+                    },
+                );
+                menu_btn(p, "Settings").observe(
+                    |_: Trigger<Pointer<Click>>, mut commands: Commands| {
+                        commands.queue(ShowSettingsScreen);
+                    },
+                );
+                menu_btn(p, "Level select").observe(
+                    |_: Trigger<Pointer<Click>>,
+                     mut game_state: ResMut<NextState<GameState>>,
+                     mut commands: Commands| {
+                        commands.queue(ClosePauseMenu { resume: false });
+                        game_state.set(GameState::LevelSelect);
+                    },
+                );
+            });
+        });
+}
+
+fn menu_btn<'a>(p: &'a mut ChildSpawnerCommands, text: &str) -> EntityCommands<'a> {
+    p.spawn((
+        Button,
+        Node {
+            width: Val::Px(200.),
+            padding: UiRect::vertical(Val::Px(8.)),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        BackgroundColor(YELLOW_400.into()),
+        BorderRadius::all(Val::Px(10.)),
+        children![(Text::new(text), TextColor(Color::BLACK))],
+    ))
+}
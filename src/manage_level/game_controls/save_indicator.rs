@@ -7,18 +7,27 @@ use bevy::window::PrimaryWindow;
 #[derive(Component)]
 pub struct SaveIndicator;
 
+/// Whether the level has been edited since it was last written to or read from a file. The
+/// indicator only follows the quicksave, this is what autosave goes by
+#[derive(Resource, Deref, Default)]
+pub struct HasUnsavedEdits(bool);
+
 const SAVED_COLOR: Srgba = LIME_400;
 const NOT_SAVED_COLOR: Srgba = ORANGE_400;
 const SIZE: f32 = 20.;
 
 pub fn save_indicator_plugin(app: &mut App) {
     app.add_systems(Startup, init)
-        .add_systems(OnEnter(GameState::InGame), show.run_if(in_editor))
+        .add_systems(
+            OnEnter(GameState::InGame),
+            (show.run_if(in_editor), set_file_saved),
+        )
         .add_systems(
             FixedUpdate,
             follow_cursor.run_if(in_state(GameState::InGame)),
         )
-        .add_systems(OnExit(GameState::InGame), hide);
+        .add_systems(OnExit(GameState::InGame), hide)
+        .init_resource::<HasUnsavedEdits>();
 }
 
 pub fn set_saved(save_indicator: Single<Entity, With<SaveIndicator>>, mut commands: Commands) {
@@ -27,12 +36,29 @@ pub fn set_saved(save_indicator: Single<Entity, With<SaveIndicator>>, mut comman
         .insert(BackgroundColor(SAVED_COLOR.into()));
 }
 
-pub fn set_not_saved(save_indicator: Single<Entity, With<SaveIndicator>>, mut commands: Commands) {
+/// For edits to the level, which leave its file out of date too
+pub fn set_not_saved(
+    save_indicator: Single<Entity, With<SaveIndicator>>,
+    mut has_unsaved_edits: ResMut<HasUnsavedEdits>,
+    commands: Commands,
+) {
+    has_unsaved_edits.0 = true;
+    show_not_saved(save_indicator, commands);
+}
+
+/// For when the arena stops matching the quicksave without the level being edited, like when the
+/// simulation runs
+pub fn show_not_saved(save_indicator: Single<Entity, With<SaveIndicator>>, mut commands: Commands) {
     commands
         .entity(*save_indicator)
         .insert(BackgroundColor(NOT_SAVED_COLOR.into()));
 }
 
+/// The level is the same as its file, after it's been saved to or loaded from one
+pub fn set_file_saved(mut has_unsaved_edits: ResMut<HasUnsavedEdits>) {
+    has_unsaved_edits.0 = false;
+}
+
 fn init(mut commands: Commands) {
     commands.spawn((
         SaveIndicator,
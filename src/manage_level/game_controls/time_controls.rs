@@ -0,0 +1,132 @@
+use bevy::{ecs::system::RunSystemOnce, input::common_conditions::input_just_pressed, prelude::*};
+use bevy_egui::input::egui_wants_any_keyboard_input;
+
+use super::{allow_game_reset, pause_menu::PauseMenuOpen, save_indicator};
+use crate::{
+    global::GameState,
+    manage_level::{IsPaused, LevelProgress, SteppingOneTick},
+};
+
+/// How fast gameplay runs compared to real time. Applied to `Time<Virtual>`, which is what
+/// `FixedUpdate` is driven by
+#[derive(Resource, Deref, DerefMut, PartialEq)]
+pub struct TimeScale(f32);
+
+impl Default for TimeScale {
+    fn default() -> Self {
+        TimeScale(1.)
+    }
+}
+
+#[derive(Component)]
+struct SpeedIndicator;
+
+const SPEEDS: [(KeyCode, f32); 5] = [
+    (KeyCode::F1, 0.25),
+    (KeyCode::F2, 0.5),
+    (KeyCode::F3, 1.),
+    (KeyCode::F4, 2.),
+    (KeyCode::F5, 4.),
+];
+
+pub fn time_controls_plugin(app: &mut App) {
+    app.add_systems(Startup, spawn_speed_indicator)
+        .add_systems(
+            PreUpdate,
+            apply_time_scale.run_if(resource_changed::<TimeScale>),
+        )
+        .add_systems(
+            Update,
+            (
+                set_time_scale_from_keys,
+                step_one_tick.run_if(
+                    input_just_pressed(KeyCode::Period)
+                        .and(in_state(IsPaused::True))
+                        .and(in_state(LevelProgress::Null))
+                        .and(not(resource_exists::<PauseMenuOpen>)),
+                ),
+            )
+                .run_if(in_state(GameState::InGame).and(not(egui_wants_any_keyboard_input))),
+        )
+        .add_systems(
+            Update,
+            update_speed_indicator.run_if(in_state(GameState::InGame)),
+        )
+        .add_systems(OnEnter(GameState::InGame), show_speed_indicator)
+        .add_systems(
+            OnExit(GameState::InGame),
+            (reset_time_scale, hide_speed_indicator),
+        )
+        .init_resource::<TimeScale>();
+}
+
+fn set_time_scale_from_keys(keys: Res<ButtonInput<KeyCode>>, mut time_scale: ResMut<TimeScale>) {
+    for (key, speed) in SPEEDS {
+        if keys.just_pressed(key) {
+            time_scale.set_if_neq(TimeScale(speed));
+        }
+    }
+}
+
+fn apply_time_scale(time_scale: Res<TimeScale>, mut time: ResMut<Time<Virtual>>) {
+    time.set_relative_speed(**time_scale);
+}
+
+fn reset_time_scale(mut time_scale: ResMut<TimeScale>) {
+    time_scale.set_if_neq(TimeScale::default());
+}
+
+/// Runs gameplay for exactly one fixed timestep while staying paused. Only the schedules the
+/// simulation lives in are run, `FixedPreUpdate` is where input is handled
+fn step_one_tick(world: &mut World) {
+    world.insert_resource(SteppingOneTick);
+
+    // The same as what `run_fixed_main_schedule` does for every timestep that's due
+    let fixed_time = world.resource::<Time<Fixed>>().as_generic();
+    *world.resource_mut::<Time>() = fixed_time;
+    world.run_schedule(FixedUpdate);
+    world.run_schedule(FixedPostUpdate);
+    let virtual_time = world.resource::<Time<Virtual>>().as_generic();
+    *world.resource_mut::<Time>() = virtual_time;
+
+    world.remove_resource::<SteppingOneTick>();
+
+    let _ = world.run_system_once(allow_game_reset);
+    let _ = world.run_system_once(save_indicator::show_not_saved);
+}
+
+fn spawn_speed_indicator(mut commands: Commands) {
+    commands.spawn((
+        SpeedIndicator,
+        Text::new(""),
+        TextFont::from_font_size(18.),
+        Node {
+            position_type: PositionType::Absolute,
+            display: Display::None,
+            left: Val::Px(10.),
+            bottom: Val::Px(10.),
+            ..default()
+        },
+        Pickable::IGNORE,
+    ));
+}
+
+fn update_speed_indicator(
+    mut speed_indicator: Single<&mut Text, With<SpeedIndicator>>,
+    time_scale: Res<TimeScale>,
+    is_paused: Res<State<IsPaused>>,
+) {
+    let mut text = format!("Speed {}x [F1-F5]", **time_scale);
+    if **is_paused == IsPaused::True {
+        text += "\n[.] to step";
+    }
+    speed_indicator.0 = text;
+}
+
+fn show_speed_indicator(mut speed_indicator: Single<&mut Node, With<SpeedIndicator>>) {
+    speed_indicator.display = Display::DEFAULT;
+}
+
+fn hide_speed_indicator(mut speed_indicator: Single<&mut Node, With<SpeedIndicator>>) {
+    speed_indicator.display = Display::None;
+}
@@ -1,13 +1,62 @@
 use bevy::{
+    color::palettes::tailwind::*,
     ecs::{schedule::ScheduleConfigs, system::ScheduleSystem},
     prelude::*,
 };
+use std::time::Duration;
 
+/// Banner across the top of the arena, for messages that stay until they're replaced
 #[derive(Component)]
 struct MessageBox;
 
+/// Holds the stack of toasts, newest at the bottom
+#[derive(Component)]
+struct ToastStack;
+
+#[derive(Component)]
+struct Toast(Timer);
+
+/// Always shows the keys that do something right now
+#[derive(Component)]
+struct ControlsHint;
+
+/// More than this and the oldest toast is dropped early
+const MAX_TOASTS: usize = 5;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ToastSeverity {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl ToastSeverity {
+    fn color(&self) -> Srgba {
+        match self {
+            ToastSeverity::Info => SKY_700,
+            ToastSeverity::Success => GREEN_700,
+            ToastSeverity::Warning => AMBER_600,
+            ToastSeverity::Error => RED_700,
+        }
+    }
+
+    /// Errors hang around longer so there's time to read them
+    fn timeout(&self) -> Duration {
+        match self {
+            ToastSeverity::Info | ToastSeverity::Success => Duration::from_secs(3),
+            ToastSeverity::Warning => Duration::from_secs(5),
+            ToastSeverity::Error => Duration::from_secs(8),
+        }
+    }
+}
+
 pub fn game_messages(app: &mut App) {
-    app.add_systems(Startup, spawn_message_box);
+    app.add_systems(
+        Startup,
+        (spawn_message_box, spawn_toast_stack, spawn_controls_hint),
+    )
+    .add_systems(Update, expire_toasts);
 }
 
 fn spawn_message_box(mut commands: Commands) {
@@ -32,6 +81,55 @@ fn spawn_message_box(mut commands: Commands) {
     ));
 }
 
+fn spawn_toast_stack(mut commands: Commands) {
+    commands.spawn((
+        ToastStack,
+        Name::new("Toast Stack"),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.),
+            right: Val::Px(10.),
+            max_width: Val::Px(400.),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::End,
+            row_gap: Val::Px(6.),
+            ..default()
+        },
+        GlobalZIndex(3),
+        Pickable::IGNORE,
+    ));
+}
+
+fn spawn_controls_hint(mut commands: Commands) {
+    commands.spawn((
+        ControlsHint,
+        Name::new("Controls Hint"),
+        Text::new(""),
+        TextFont::from_font_size(16.),
+        TextLayout::new_with_justify(JustifyText::Right),
+        Node {
+            position_type: PositionType::Absolute,
+            right: Val::Px(10.),
+            bottom: Val::Px(10.),
+            ..default()
+        },
+        Pickable::IGNORE,
+    ));
+}
+
+fn expire_toasts(
+    toasts: Query<(Entity, &mut Toast)>,
+    time: Res<Time<Real>>,
+    mut commands: Commands,
+) {
+    for (entity, mut toast) in toasts {
+        if toast.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// Sets the banner, an empty message hides it
 pub fn set_message(message: &'static str) -> ScheduleConfigs<ScheduleSystem> {
     return (move |mut commands: Commands, message_box: Single<Entity, With<MessageBox>>| {
         commands.entity(*message_box).insert(Text::new(message));
@@ -39,6 +137,7 @@ pub fn set_message(message: &'static str) -> ScheduleConfigs<ScheduleSystem> {
     .into_configs();
 }
 
+/// Sets the banner, an empty message hides it
 pub struct SetMessage(pub String);
 impl Command for SetMessage {
     fn apply(self, world: &mut World) -> () {
@@ -48,3 +147,64 @@ impl Command for SetMessage {
         *message_box = Text(self.0);
     }
 }
+
+/// A short lived message stacked in the corner, so it doesn't replace whatever the banner says
+pub struct ShowToast {
+    pub message: String,
+    pub severity: ToastSeverity,
+}
+
+impl ShowToast {
+    pub fn new(severity: ToastSeverity, message: impl Into<String>) -> Self {
+        ShowToast {
+            message: message.into(),
+            severity,
+        }
+    }
+}
+
+impl Command for ShowToast {
+    fn apply(self, world: &mut World) -> () {
+        let mut stack_q = world.query_filtered::<Entity, With<ToastStack>>();
+        let stack = stack_q.single(world).unwrap();
+
+        // Toasts are only ever added at the end, so the first children are the oldest
+        let toasts: Vec<Entity> = world
+            .get::<Children>(stack)
+            .map_or(Vec::new(), |children| children.to_vec());
+        for toast in toasts
+            .iter()
+            .take((toasts.len() + 1).saturating_sub(MAX_TOASTS))
+        {
+            world.despawn(*toast);
+        }
+
+        let toast = world
+            .spawn((
+                Toast(Timer::new(self.severity.timeout(), TimerMode::Once)),
+                Node {
+                    padding: UiRect::axes(Val::Px(12.), Val::Px(6.)),
+                    ..default()
+                },
+                BackgroundColor(self.severity.color().into()),
+                BorderRadius::all(Val::Px(8.)),
+                Pickable::IGNORE,
+                children![(Text::new(self.message), Pickable::IGNORE)],
+            ))
+            .id();
+        world.entity_mut(stack).add_child(toast);
+    }
+}
+
+/// Replaces what the controls hint in the corner says
+pub struct SetControlsHint(pub String);
+impl Command for SetControlsHint {
+    fn apply(self, world: &mut World) -> () {
+        let mut q = world.query_filtered::<&mut Text, With<ControlsHint>>();
+        let mut controls_hint = q.single_mut(world).unwrap();
+
+        if controls_hint.0 != self.0 {
+            controls_hint.0 = self.0;
+        }
+    }
+}
@@ -0,0 +1,291 @@
+use bevy::{ecs::system::RunSystemOnce, prelude::*};
+use bevy_egui::{egui, EguiContextPass, EguiContexts};
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use crate::{
+    card::CardConsts,
+    global::{GameState, InEditorRes},
+    load_error::{read_json_file, ShowLoadError},
+};
+
+use super::{
+    game_controls::save_indicator::{self, HasUnsavedEdits},
+    game_messages::{ShowToast, ToastSeverity},
+    read_level_file, reset_level_progress, spawn_entities_from_level_memory, Level, Pause,
+    ReplaceLevel,
+};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Where the level being played came from, so it can be reloaded when that changes
+#[derive(Resource)]
+pub enum LevelSource {
+    /// Reloaded by the asset watcher
+    Asset(Handle<Level>),
+    /// Files outside of the assets folder have no watcher, their modified time is polled instead
+    File(WatchedFile),
+}
+
+/// Constants file that was loaded on top of the level
+#[derive(Resource)]
+pub struct WatchedConstsFile(pub WatchedFile);
+
+pub struct WatchedFile {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+}
+
+impl WatchedFile {
+    /// Call again after writing to the file so saving doesn't count as a change
+    pub fn new(path: &Path) -> Self {
+        WatchedFile {
+            path: path.to_path_buf(),
+            modified: modified_time(path),
+        }
+    }
+
+    fn has_changed(&mut self) -> bool {
+        let modified = modified_time(&self.path);
+        // A missing file is usually a text editor halfway through saving, wait for it to return
+        if modified.is_none() || modified == self.modified {
+            return false;
+        }
+
+        self.modified = modified;
+        true
+    }
+
+    fn file_name(&self) -> String {
+        file_name(&self.path)
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map_or(String::new(), |name| name.to_string_lossy().to_string())
+}
+
+#[derive(Resource)]
+struct PollTimer(Timer);
+
+pub fn hot_reload_plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            reload_level_asset.run_if(resource_exists::<LevelSource>),
+            (
+                tick_poll_timer,
+                (poll_level_file, poll_consts_file).run_if(poll_is_due),
+            )
+                .chain(),
+        )
+            .run_if(in_state(GameState::InGame)),
+    )
+    .add_systems(
+        EguiContextPass,
+        show_reload_prompt.run_if(resource_exists::<PendingReload>),
+    )
+    .add_systems(
+        OnExit(GameState::InGame),
+        (stop_watching, withdraw_reload_prompt),
+    )
+    .insert_resource(PollTimer(Timer::new(POLL_INTERVAL, TimerMode::Repeating)));
+}
+
+fn tick_poll_timer(mut timer: ResMut<PollTimer>, time: Res<Time<Real>>) {
+    timer.0.tick(time.delta());
+}
+
+fn poll_is_due(timer: Res<PollTimer>) -> bool {
+    timer.0.just_finished()
+}
+
+fn reload_level_asset(
+    mut asset_events: EventReader<AssetEvent<Level>>,
+    level_source: Res<LevelSource>,
+    level_assets: Res<Assets<Level>>,
+    mut commands: Commands,
+) {
+    let LevelSource::Asset(handle) = &*level_source else {
+        return;
+    };
+
+    for asset_event in asset_events.read() {
+        if !asset_event.is_modified(handle) {
+            continue;
+        }
+
+        if let Some(level) = level_assets.get(handle).cloned() {
+            let file_name = handle
+                .path()
+                .map_or(String::from("The level"), |path| file_name(path.path()));
+            commands.queue(HotReload {
+                level: Some(level),
+                file_name,
+            });
+        }
+    }
+}
+
+fn poll_level_file(level_source: Option<ResMut<LevelSource>>, mut commands: Commands) {
+    let Some(mut level_source) = level_source else {
+        return;
+    };
+    let LevelSource::File(watched_file) = &mut *level_source else {
+        return;
+    };
+
+    if !watched_file.has_changed() {
+        return;
+    }
+
+    match read_level_file(&watched_file.path) {
+        Ok(loaded) => commands.queue(HotReload {
+            level: Some(loaded.level),
+            file_name: watched_file.file_name(),
+        }),
+        Err(err) => commands.queue(ShowLoadError(err)),
+    }
+}
+
+fn poll_consts_file(consts_file: Option<ResMut<WatchedConstsFile>>, mut commands: Commands) {
+    let Some(mut consts_file) = consts_file else {
+        return;
+    };
+
+    if !consts_file.0.has_changed() {
+        return;
+    }
+
+    commands.queue(HotReload {
+        level: None,
+        file_name: consts_file.0.file_name(),
+    });
+}
+
+/// Puts the watched constants file back on top of the level's constants. Returns false if there's
+/// no constants file or it couldn't be read
+fn apply_consts_file(world: &mut World) -> bool {
+    let Some(consts_file) = world.get_resource::<WatchedConstsFile>() else {
+        return false;
+    };
+
+    match read_json_file::<CardConsts>(&consts_file.0.path) {
+        Ok(card_consts) => {
+            *world.resource_mut::<CardConsts>() = card_consts;
+            true
+        }
+        Err(err) => {
+            ShowLoadError(err).apply(world);
+            false
+        }
+    }
+}
+
+fn restart_after_reload(world: &mut World, file_name: &str) {
+    Pause.apply(world);
+    let _ = world.run_system_once(reset_level_progress);
+    // What's in the arena now is exactly what's on disk
+    let _ = world.run_system_once(save_indicator::set_saved);
+    ShowToast::new(
+        ToastSeverity::Info,
+        format!("{file_name} changed on disk, the level was reloaded"),
+    )
+    .apply(world);
+}
+
+struct HotReload {
+    /// The level file's new contents, `None` when only the constants file changed
+    level: Option<Level>,
+    file_name: String,
+}
+
+impl HotReload {
+    fn reload(self, world: &mut World) {
+        let Some(level) = self.level else {
+            if apply_consts_file(world) {
+                let _ = world.run_system_once(spawn_entities_from_level_memory);
+                restart_after_reload(world, &self.file_name);
+            }
+            return;
+        };
+
+        ReplaceLevel(level).apply(world);
+        let _ = world.run_system_once(save_indicator::set_file_saved);
+
+        // Replacing the level resets the constants to the level's own
+        if apply_consts_file(world) {
+            let _ = world.run_system_once(spawn_entities_from_level_memory);
+        }
+
+        restart_after_reload(world, &self.file_name);
+    }
+}
+
+impl Command for HotReload {
+    fn apply(self, world: &mut World) {
+        let loses_no_edits =
+            !**world.resource::<InEditorRes>() || !**world.resource::<HasUnsavedEdits>();
+        if loses_no_edits {
+            self.reload(world);
+            return;
+        }
+
+        // Reloading the level file applies the constants file too, so it covers both
+        let level_is_pending = world
+            .get_resource::<PendingReload>()
+            .is_some_and(|pending| pending.0.level.is_some());
+        if self.level.is_some() || !level_is_pending {
+            world.insert_resource(PendingReload(self));
+        }
+    }
+}
+
+/// A reload that would throw away the editor's unsaved edits, held until the player decides
+#[derive(Resource)]
+struct PendingReload(HotReload);
+
+fn show_reload_prompt(
+    mut contexts: EguiContexts,
+    pending_reload: Res<PendingReload>,
+    mut commands: Commands,
+) {
+    egui::Window::new("Reload changed file?")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, (0., 0.))
+        .show(contexts.ctx_mut(), |ui| {
+            ui.label(format!("{} changed on disk.", pending_reload.0.file_name));
+            ui.label("Reloading it will lose the edits that haven't been saved.");
+
+            ui.horizontal(|ui| {
+                if ui.button("Reload").clicked() {
+                    commands.queue(|world: &mut World| {
+                        if let Some(pending) = world.remove_resource::<PendingReload>() {
+                            pending.0.reload(world);
+                        }
+                    });
+                }
+
+                if ui.button("Keep my changes").clicked() {
+                    commands.remove_resource::<PendingReload>();
+                }
+            });
+        });
+}
+
+fn withdraw_reload_prompt(mut commands: Commands) {
+    commands.remove_resource::<PendingReload>();
+}
+
+fn stop_watching(mut commands: Commands) {
+    commands.remove_resource::<LevelSource>();
+    commands.remove_resource::<WatchedConstsFile>();
+}
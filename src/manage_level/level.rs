@@ -1,26 +1,46 @@
-use bevy::prelude::*;
-use bevy_common_assets::json::JsonAssetPlugin;
+mod metadata;
+mod migrations;
+mod share_code;
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, LoadContext},
+    prelude::*,
+};
+pub use metadata::{Difficulty, LevelMetadata, Par};
+pub use migrations::{read_level_file, CURRENT_LEVEL_VERSION};
 use serde::{Deserialize, Serialize};
+pub use share_code::{decode_share_code, encode_share_code};
 
 use crate::{
-    card::{Card, MaybeCard, SpawnedCard},
+    card::{Card, CardConsts, CardConstsOverrides, MaybeCard, SpawnedCard},
     deckbar::DeckBarRoot,
+    load_error::LoadError,
+    placement::{PlacementZone, PlacementZones},
+    terrain::{Terrain, TerrainTile},
 };
 
 use super::LevelMemory;
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct DeathGoal {
     pub card: Card,
     pub count_dead: u32,
 }
 
-#[derive(Serialize, Deserialize, Asset, TypePath, Debug)]
+#[derive(Serialize, Deserialize, Asset, TypePath, Debug, Clone)]
 pub struct Level {
+    pub version: u32,
+    pub metadata: LevelMetadata,
     pub cards: Vec<(Card, Vec2)>,
     pub starting_deckbar: Vec<Card>,
     pub win_condition: DeathGoal,
     pub lose_condition: DeathGoal,
+    #[serde(default, skip_serializing_if = "CardConstsOverrides::is_empty")]
+    pub consts_overrides: CardConstsOverrides,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub terrain: Vec<TerrainTile>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub placement_zones: Vec<PlacementZone>,
 }
 
 impl Level {
@@ -57,12 +77,101 @@ impl Level {
         let level_in_memory = world.get_resource::<LevelMemory>().unwrap();
         current_level.win_condition = level_in_memory.win_condition.clone();
         current_level.lose_condition = level_in_memory.lose_condition.clone();
+        current_level.metadata = level_in_memory.metadata.clone();
+
+        current_level.consts_overrides =
+            CardConstsOverrides::from_diff(&CardConsts::default(), world.resource::<CardConsts>());
+
+        current_level.terrain = world.resource::<Terrain>().to_tiles();
+        current_level.placement_zones = world.resource::<PlacementZones>().0.clone();
 
         current_level
     }
 
+    /// Human readable list of what changed going from `self` to `other`
+    pub fn diff(&self, other: &Level) -> Vec<String> {
+        let mut changes = Vec::new();
+
+        let describe = |(card, position): &(Card, Vec2)| {
+            format!(
+                "{} at ({:.0}, {:.0})",
+                card.to_string(),
+                position.x,
+                position.y
+            )
+        };
+
+        let mut unmatched_other: Vec<&(Card, Vec2)> = other.cards.iter().collect();
+        for card in &self.cards {
+            let matching = unmatched_other
+                .iter()
+                .position(|other_card| other_card.0 == card.0 && other_card.1 == card.1);
+
+            match matching {
+                Some(i) => {
+                    unmatched_other.swap_remove(i);
+                }
+                None => changes.push(format!("- {}", describe(card))),
+            }
+        }
+        for card in unmatched_other {
+            changes.push(format!("+ {}", describe(card)));
+        }
+
+        if self.starting_deckbar != other.starting_deckbar {
+            let deck_string = |deck: &Vec<Card>| {
+                deck.iter()
+                    .map(|card| card.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            };
+            changes.push(format!(
+                "Deck: [{}] -> [{}]",
+                deck_string(&self.starting_deckbar),
+                deck_string(&other.starting_deckbar)
+            ));
+        }
+
+        let goal_string =
+            |goal: &DeathGoal| format!("{} {}", goal.count_dead, goal.card.to_string());
+        if self.win_condition != other.win_condition {
+            changes.push(format!(
+                "Win condition: {} -> {}",
+                goal_string(&self.win_condition),
+                goal_string(&other.win_condition)
+            ));
+        }
+        if self.lose_condition != other.lose_condition {
+            changes.push(format!(
+                "Lose condition: {} -> {}",
+                goal_string(&self.lose_condition),
+                goal_string(&other.lose_condition)
+            ));
+        }
+
+        if self.consts_overrides != other.consts_overrides {
+            changes.push("Constants changed".to_string());
+        }
+
+        if self.terrain != other.terrain {
+            changes.push("Terrain changed".to_string());
+        }
+
+        if self.placement_zones != other.placement_zones {
+            changes.push("Placement zones changed".to_string());
+        }
+
+        if self.metadata != other.metadata {
+            changes.push("Metadata changed".to_string());
+        }
+
+        changes
+    }
+
     pub fn get_stub() -> Level {
         Level {
+            version: CURRENT_LEVEL_VERSION,
+            metadata: LevelMetadata::default(),
             cards: Vec::new(),
             starting_deckbar: Vec::new(),
             win_condition: DeathGoal {
@@ -73,10 +182,45 @@ impl Level {
                 card: Card::Quakka,
                 count_dead: 1,
             },
+            consts_overrides: CardConstsOverrides::default(),
+            terrain: Vec::new(),
+            placement_zones: Vec::new(),
         }
     }
 }
 
 pub fn level_plugin(app: &mut App) {
-    app.add_plugins(JsonAssetPlugin::<Level>::new(&["level.json"]));
+    app.init_asset::<Level>().init_asset_loader::<LevelLoader>();
+}
+
+/// Loads level files through the migrations so older levels can still be opened from the assets
+#[derive(Default)]
+struct LevelLoader;
+
+impl AssetLoader for LevelLoader {
+    type Asset = Level;
+    type Settings = ();
+    type Error = LoadError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Level, LoadError> {
+        let mut bytes = Vec::new();
+        reader
+            .read_to_end(&mut bytes)
+            .await
+            .map_err(|error| LoadError::Read {
+                path: load_context.path().to_path_buf(),
+                error,
+            })?;
+
+        Ok(migrations::parse_level(load_context.path(), &bytes)?.level)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["level.json"]
+    }
 }
@@ -0,0 +1,72 @@
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+
+/// Information about a level for the player, none of it affects how the level plays
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct LevelMetadata {
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub name: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub author: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub difficulty: Option<Difficulty>,
+    /// Revealed one at a time when the player asks for a hint
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub hints: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub par: Option<Par>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, EnumIter)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl Difficulty {
+    pub fn to_string(&self) -> String {
+        match self {
+            Difficulty::Easy => "Easy".to_string(),
+            Difficulty::Medium => "Medium".to_string(),
+            Difficulty::Hard => "Hard".to_string(),
+        }
+    }
+}
+
+/// What a good run of the level looks like
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct Par {
+    pub cards_used: u32,
+    pub time_secs: f32,
+}
+
+impl Default for Par {
+    fn default() -> Self {
+        Par {
+            cards_used: 1,
+            time_secs: 30.,
+        }
+    }
+}
+
+impl Par {
+    pub fn to_string(&self) -> String {
+        let s = if self.cards_used == 1 { "" } else { "s" };
+        format!("{} card{s} in {:.0}s", self.cards_used, self.time_secs)
+    }
+}
+
+impl LevelMetadata {
+    /// The name to show for the level, falling back to `fallback` if it was never given one
+    pub fn display_name(&self, fallback: &str) -> String {
+        if self.name.trim().is_empty() {
+            fallback.to_string()
+        } else {
+            self.name.clone()
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use serde_json::{Map, Value};
+use std::path::Path;
+
+use crate::load_error::{parse_json, LoadError};
+
+use super::Level;
+
+/// Bump this and add a migration to [`MIGRATIONS`] whenever the shape of [`Level`] changes
+pub const CURRENT_LEVEL_VERSION: u32 = 3;
+
+/// `MIGRATIONS[n]` upgrades a version `n` level to version `n + 1`
+const MIGRATIONS: [fn(&mut Map<String, Value>); CURRENT_LEVEL_VERSION as usize] =
+    [v0_to_v1, v1_to_v2, v2_to_v3];
+
+/// Levels from before versioning have no version field at all. Their shape is the same as version
+/// 1, the version number just has to be filled in
+fn v0_to_v1(_level: &mut Map<String, Value>) {}
+
+/// Version 2 added the level's name, author and so on under `metadata`
+fn v1_to_v2(level: &mut Map<String, Value>) {
+    level.insert("metadata".to_string(), Value::Object(Map::new()));
+}
+
+/// Version 3 added `placement_zones`, older levels let every card go anywhere
+fn v2_to_v3(level: &mut Map<String, Value>) {
+    level.insert("placement_zones".to_string(), Value::Array(Vec::new()));
+}
+
+pub struct LoadedLevel {
+    pub level: Level,
+    /// The version the file was written in, if it had to be upgraded
+    pub migrated_from: Option<u32>,
+}
+
+pub fn read_level_file(path: &Path) -> Result<LoadedLevel, LoadError> {
+    let bytes = std::fs::read(path).map_err(|error| LoadError::Read {
+        path: path.to_path_buf(),
+        error,
+    })?;
+
+    parse_level(path, &bytes)
+}
+
+/// Parses a level written in any version, upgrading it to [`CURRENT_LEVEL_VERSION`]
+pub fn parse_level(path: &Path, bytes: &[u8]) -> Result<LoadedLevel, LoadError> {
+    let mut value: Value = parse_json(path, bytes)?;
+    let migrated_from = migrate(&mut value).map_err(|message| LoadError::Migration {
+        path: path.to_path_buf(),
+        message,
+    })?;
+
+    let level = match migrated_from {
+        // Parsing the text again rather than the value keeps line numbers in error messages
+        None => parse_json(path, bytes)?,
+        Some(_) => serde_path_to_error::deserialize(value).map_err(|err| LoadError::Parse {
+            path: path.to_path_buf(),
+            field: err.path().to_string(),
+            line: 0,
+            column: 0,
+            message: err.into_inner().to_string(),
+        })?,
+    };
+
+    Ok(LoadedLevel {
+        level,
+        migrated_from,
+    })
+}
+
+/// Upgrades `level` in place, returning the version it started at if it wasn't already current
+fn migrate(level: &mut Value) -> Result<Option<u32>, String> {
+    let Some(level) = level.as_object_mut() else {
+        return Err("a level has to be a JSON object".to_string());
+    };
+
+    let version = match level.get("version") {
+        None => 0,
+        Some(version) => version
+            .as_u64()
+            .and_then(|version| u32::try_from(version).ok())
+            .ok_or_else(|| format!("`version` should be a whole number, not {version}"))?,
+    };
+
+    if version > CURRENT_LEVEL_VERSION {
+        return Err(format!(
+            "it was made with a newer version of the game (level version {version}, this game \
+             understands up to {CURRENT_LEVEL_VERSION})"
+        ));
+    }
+
+    if version == CURRENT_LEVEL_VERSION {
+        return Ok(None);
+    }
+
+    for migration in &MIGRATIONS[version as usize..] {
+        migration(level);
+    }
+    level.insert("version".to_string(), CURRENT_LEVEL_VERSION.into());
+
+    Ok(Some(version))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn golden_dir() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/golden_levels")
+    }
+
+    /// Every historical version has a `v<n>.level.json` and the `v<n>.expected.level.json` it
+    /// should turn into once loaded and saved again
+    #[test]
+    fn golden_files_migrate_to_expected() {
+        for version in 0..=CURRENT_LEVEL_VERSION {
+            let input = golden_dir().join(format!("v{version}.level.json"));
+            let expected = golden_dir().join(format!("v{version}.expected.level.json"));
+
+            let loaded = read_level_file(&input)
+                .unwrap_or_else(|err| panic!("golden level v{version} failed to load: {err}"));
+            assert_eq!(
+                loaded.migrated_from,
+                (version != CURRENT_LEVEL_VERSION).then_some(version)
+            );
+
+            let expected: Value =
+                serde_json::from_slice(&std::fs::read(&expected).unwrap()).unwrap();
+            assert_eq!(
+                serde_json::to_value(&loaded.level).unwrap(),
+                expected,
+                "golden level v{version} didn't migrate to {}",
+                golden_dir()
+                    .join(format!("v{version}.expected.level.json"))
+                    .display()
+            );
+        }
+    }
+
+    #[test]
+    fn shipped_levels_load() {
+        let levels_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("assets/levels");
+        for level in std::fs::read_dir(levels_dir).unwrap() {
+            let level = level.unwrap().path();
+            if let Err(err) = read_level_file(&level) {
+                panic!("{err}");
+            }
+        }
+    }
+
+    #[test]
+    fn newer_versions_are_rejected() {
+        let level = format!(r#"{{ "version": {} }}"#, CURRENT_LEVEL_VERSION + 1);
+        let result = parse_level(Path::new("newer.level.json"), level.as_bytes());
+        assert!(matches!(result, Err(LoadError::Migration { .. })));
+    }
+}
@@ -0,0 +1,152 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use flate2::{read::DeflateDecoder, write::DeflateEncoder, Compression};
+use std::{
+    io::{Read, Write},
+    path::Path,
+};
+
+use crate::load_error::LoadError;
+
+use super::{migrations::parse_level, Level};
+
+/// Every code starts with this, the number is bumped if the encoding itself ever changes. Changes
+/// to [`Level`] are handled by the level's own version instead
+const SHARE_CODE_PREFIX: &str = "DS1-";
+
+/// Far bigger than any real level. A code that inflates past this is rejected rather than filling
+/// up memory
+const MAX_LEVEL_BYTES: u64 = 1024 * 1024;
+
+/// Turns a level into a string short enough to paste into a chat message.
+///
+/// The code is the level's JSON, deflated, with a CRC32 of the JSON in front so typos are caught,
+/// all in URL safe base64
+pub fn encode_share_code(level: &Level) -> String {
+    encode_json(&serde_json::to_vec(level).unwrap())
+}
+
+fn encode_json(json: &[u8]) -> String {
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::best());
+    encoder.write_all(json).unwrap();
+    let compressed = encoder.finish().unwrap();
+
+    let mut bytes = crc32fast::hash(json).to_be_bytes().to_vec();
+    bytes.extend(compressed);
+
+    format!("{SHARE_CODE_PREFIX}{}", URL_SAFE_NO_PAD.encode(bytes))
+}
+
+pub fn decode_share_code(code: &str) -> Result<Level, LoadError> {
+    let share_code_error = |message: &str| LoadError::ShareCode {
+        message: message.to_string(),
+    };
+
+    // Whitespace sneaks in when codes get wrapped in chat messages
+    let code: String = code.chars().filter(|c| !c.is_whitespace()).collect();
+
+    let Some(data) = code.strip_prefix(SHARE_CODE_PREFIX) else {
+        return Err(share_code_error(
+            "it should start with DS1-, it might be from a newer version of the game",
+        ));
+    };
+
+    let bytes = URL_SAFE_NO_PAD
+        .decode(data)
+        .map_err(|_| share_code_error("it has characters that can't be in a level code"))?;
+
+    if bytes.len() < 4 {
+        return Err(share_code_error("it's too short"));
+    }
+    let (checksum, compressed) = bytes.split_at(4);
+
+    // One byte past the limit is enough to tell it's over
+    let mut json = Vec::new();
+    DeflateDecoder::new(compressed)
+        .take(MAX_LEVEL_BYTES + 1)
+        .read_to_end(&mut json)
+        .map_err(|_| share_code_error("it's incomplete or has a typo"))?;
+
+    if json.len() as u64 > MAX_LEVEL_BYTES {
+        return Err(share_code_error("it's far too big to be a level"));
+    }
+
+    if crc32fast::hash(&json).to_be_bytes() != checksum {
+        return Err(share_code_error("it's incomplete or has a typo"));
+    }
+
+    parse_level(Path::new("level code"), &json).map(|loaded| loaded.level)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn share_code_message(result: Result<Level, LoadError>) -> String {
+        match result {
+            Err(LoadError::ShareCode { message }) => message,
+            Err(err) => panic!("expected a share code error, got {err}"),
+            Ok(_) => panic!("expected a share code error, the code decoded"),
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut level = Level::get_stub();
+        level.metadata.name = "Round trip".to_string();
+
+        let decoded = decode_share_code(&encode_share_code(&level)).unwrap();
+        assert_eq!(
+            serde_json::to_value(&decoded).unwrap(),
+            serde_json::to_value(&level).unwrap()
+        );
+    }
+
+    #[test]
+    fn bad_prefix_is_rejected() {
+        let code = encode_share_code(&Level::get_stub()).replacen("DS1-", "DS9-", 1);
+        assert!(share_code_message(decode_share_code(&code)).contains("DS1-"));
+    }
+
+    #[test]
+    fn typo_fails_the_checksum() {
+        let mut code: Vec<char> = encode_share_code(&Level::get_stub()).chars().collect();
+        // The first characters after the prefix only hold the checksum
+        let typo = SHARE_CODE_PREFIX.len() + 1;
+        code[typo] = if code[typo] == 'A' { 'B' } else { 'A' };
+        let code: String = code.into_iter().collect();
+
+        assert!(share_code_message(decode_share_code(&code)).contains("typo"));
+    }
+
+    #[test]
+    fn truncated_code_is_rejected() {
+        let code = encode_share_code(&Level::get_stub());
+        let truncated = &code[..code.len() / 2];
+        share_code_message(decode_share_code(truncated));
+    }
+
+    #[test]
+    fn oversized_code_is_rejected() {
+        let code = encode_json(&vec![b' '; MAX_LEVEL_BYTES as usize * 2]);
+        assert!(share_code_message(decode_share_code(&code)).contains("too big"));
+    }
+
+    /// The level's JSON padded out with trailing whitespace, which is still valid JSON
+    fn level_json_of_len(len: usize) -> Vec<u8> {
+        let mut json = serde_json::to_vec(&Level::get_stub()).unwrap();
+        json.resize(len, b' ');
+        json
+    }
+
+    #[test]
+    fn level_right_at_the_limit_decodes() {
+        let code = encode_json(&level_json_of_len(MAX_LEVEL_BYTES as usize));
+        assert!(decode_share_code(&code).is_ok());
+    }
+
+    #[test]
+    fn level_one_byte_over_the_limit_is_rejected() {
+        let code = encode_json(&level_json_of_len(MAX_LEVEL_BYTES as usize + 1));
+        assert!(share_code_message(decode_share_code(&code)).contains("too big"));
+    }
+}
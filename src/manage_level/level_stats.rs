@@ -0,0 +1,91 @@
+use bevy::prelude::*;
+
+use crate::{
+    card::{combat_events::DamageDealt, Card, CardDeath},
+    global::GameState,
+};
+
+use super::{simulation_running, LevelProgress};
+
+/// What happened during the current attempt at the level, gathered as it's played
+#[derive(Resource, Default, Clone)]
+pub struct LevelStats {
+    /// Seconds of unpaused play
+    pub elapsed: f32,
+    pub cards_placed: Vec<(Card, u32)>,
+    pub damage_dealt: Vec<(Card, f32)>,
+    pub deaths: Vec<(Card, u32)>,
+}
+
+impl LevelStats {
+    pub fn total_cards_placed(&self) -> u32 {
+        self.cards_placed.iter().map(|(_, count)| count).sum()
+    }
+
+    pub fn deaths_of(&self, card: Card) -> u32 {
+        self.deaths
+            .iter()
+            .find(|(c, _)| *c == card)
+            .map_or(0, |(_, count)| *count)
+    }
+
+    pub fn card_placed(&mut self, card: Card) {
+        add_to(&mut self.cards_placed, card, 1);
+    }
+}
+
+/// Adds `amount` to `card`'s total, giving it a total first if it doesn't have one
+fn add_to<T: std::ops::AddAssign + Default>(totals: &mut Vec<(Card, T)>, card: Card, amount: T) {
+    match totals.iter_mut().find(|(c, _)| *c == card) {
+        Some((_, total)) => *total += amount,
+        None => {
+            let mut total = T::default();
+            total += amount;
+            totals.push((card, total));
+        }
+    }
+}
+
+pub fn level_stats_plugin(app: &mut App) {
+    app.add_systems(
+        FixedUpdate,
+        tick_elapsed.run_if(
+            in_state(GameState::InGame)
+                .and(simulation_running)
+                .and(in_state(LevelProgress::Null)),
+        ),
+    )
+    .add_systems(
+        FixedPostUpdate,
+        (count_damage, count_deaths).run_if(in_state(GameState::InGame)),
+    )
+    .init_resource::<LevelStats>();
+}
+
+/// Called whenever the level is spawned from scratch
+pub fn clear_level_stats(mut level_stats: ResMut<LevelStats>) {
+    *level_stats = LevelStats::default();
+}
+
+fn tick_elapsed(mut level_stats: ResMut<LevelStats>, time: Res<Time>) {
+    level_stats.elapsed += time.delta_secs();
+}
+
+fn count_damage(
+    mut damage_dealt_evr: EventReader<DamageDealt>,
+    mut level_stats: ResMut<LevelStats>,
+) {
+    for damage_dealt in damage_dealt_evr.read() {
+        add_to(
+            &mut level_stats.damage_dealt,
+            damage_dealt.attacker_card,
+            damage_dealt.amount,
+        );
+    }
+}
+
+fn count_deaths(mut card_death_evr: EventReader<CardDeath>, mut level_stats: ResMut<LevelStats>) {
+    for card_death in card_death_evr.read() {
+        add_to(&mut level_stats.deaths, **card_death, 1);
+    }
+}
@@ -0,0 +1,270 @@
+use bevy::{ecs::system::RunSystemOnce, prelude::*};
+use bevy_egui::egui::{Slider, Ui};
+use std::time::Duration;
+use DuckSlayer::delete_all;
+
+use crate::{
+    card::{
+        combat_events::{CombatLog, CombatLogCheckpoint},
+        spawn_card, Attacker, Card, CardConsts, FollowPath, Health, SpawnedCard, Waterball,
+    },
+    deckbar::{clear_deckbar, PushToDeckbar},
+    global::{in_editor, GameState},
+    placement::PlacementZones,
+    terrain::Terrain,
+};
+
+use super::{
+    simulation_running, Level, LevelEntity, LevelProgress, LevelStats, Pause, WinLoseDeathProgress,
+};
+
+const SNAPSHOT_INTERVAL: f32 = 0.5;
+/// Five minutes of history, older snapshots are dropped
+const MAX_SNAPSHOTS: usize = 600;
+
+/// Everything needed to put a card back exactly how it was mid simulation
+#[derive(Clone)]
+struct CardState {
+    card: Card,
+    position: Vec2,
+    health: Option<f32>,
+    attacker: Option<AttackerState>,
+    /// Goal and speed, the path itself is found again from the restored position
+    follow_path: Option<((i32, i32), f32)>,
+    waterball_elapsed: Option<Duration>,
+}
+
+#[derive(Clone)]
+struct AttackerState {
+    cooldown_elapsed: Duration,
+    /// Index into the snapshot's cards, entities don't survive being restored
+    victim: Option<usize>,
+    victim_in_range_fraction: Option<f32>,
+}
+
+#[derive(Clone)]
+struct SimSnapshot {
+    /// Simulated seconds since the level started
+    elapsed: f32,
+    /// Deck, conditions, constants, terrain and zones. Its cards are ignored in favour of `cards`
+    level: Level,
+    cards: Vec<CardState>,
+    progress: WinLoseDeathProgress,
+    stats: LevelStats,
+    combat_log: CombatLogCheckpoint,
+}
+
+/// Snapshots of the running simulation in the editor, so it can be rewound and branched from
+#[derive(Resource, Default)]
+pub struct Timeline {
+    snapshots: Vec<SimSnapshot>,
+    elapsed: f32,
+    since_last_snapshot: f32,
+    /// Snapshot that was rewound to. Unpausing drops every snapshot after it
+    rewound_to: Option<usize>,
+}
+
+pub fn timeline_plugin(app: &mut App) {
+    app.add_systems(
+        FixedUpdate,
+        record_snapshot.run_if(
+            in_state(GameState::InGame)
+                .and(in_editor)
+                .and(simulation_running)
+                .and(in_state(LevelProgress::Null)),
+        ),
+    )
+    .add_systems(OnExit(GameState::InGame), clear_timeline)
+    .init_resource::<Timeline>();
+}
+
+/// Called whenever the level is spawned from scratch, the history is for a different run
+pub fn clear_timeline(mut timeline: ResMut<Timeline>) {
+    *timeline = Timeline::default();
+}
+
+fn record_snapshot(world: &mut World) {
+    let delta = world.resource::<Time>().delta_secs();
+
+    let mut timeline = world.resource_mut::<Timeline>();
+    if let Some(combat_log) = branch_from_rewound(&mut timeline) {
+        world.resource_mut::<CombatLog>().rewind(combat_log);
+    }
+
+    let mut timeline = world.resource_mut::<Timeline>();
+
+    let is_first = timeline.snapshots.is_empty();
+    if !is_first {
+        timeline.elapsed += delta;
+        timeline.since_last_snapshot += delta;
+        if timeline.since_last_snapshot < SNAPSHOT_INTERVAL {
+            return;
+        }
+    }
+    timeline.since_last_snapshot = 0.;
+
+    let elapsed = timeline.elapsed;
+    let snapshot = take_snapshot(world, elapsed);
+
+    let mut timeline = world.resource_mut::<Timeline>();
+    timeline.snapshots.push(snapshot);
+    if timeline.snapshots.len() > MAX_SNAPSHOTS {
+        timeline.snapshots.remove(0);
+    }
+}
+
+/// Carrying on from a rewound snapshot starts a new branch of history. Returns where the combat
+/// log should be rewound to, what it has after that is from the dropped branch
+fn branch_from_rewound(timeline: &mut Timeline) -> Option<CombatLogCheckpoint> {
+    let rewound_to = timeline.rewound_to.take()?;
+
+    timeline.snapshots.truncate(rewound_to + 1);
+    timeline.elapsed = timeline.snapshots[rewound_to].elapsed;
+    timeline.since_last_snapshot = 0.;
+
+    Some(timeline.snapshots[rewound_to].combat_log)
+}
+
+fn take_snapshot(world: &mut World, elapsed: f32) -> SimSnapshot {
+    let level = Level::get_current(world);
+    let progress = world.resource::<WinLoseDeathProgress>().clone();
+    let stats = world.resource::<LevelStats>().clone();
+    let combat_log = world.resource::<CombatLog>().checkpoint();
+
+    let mut cards_q = world.query_filtered::<(
+        Entity,
+        &SpawnedCard,
+        &Transform,
+        Option<&Health>,
+        Option<&Attacker>,
+        Option<&FollowPath>,
+        Option<&Waterball>,
+    ), With<LevelEntity>>();
+
+    let entities: Vec<Entity> = cards_q.iter(world).map(|(e, ..)| e).collect();
+    let cards = cards_q
+        .iter(world)
+        .map(
+            |(_, card, transform, health, attacker, follow_path, waterball)| CardState {
+                card: **card,
+                position: transform.translation.truncate(),
+                health: health.map(|health| health.current_health),
+                attacker: attacker.map(|attacker| AttackerState {
+                    cooldown_elapsed: attacker.cooldown_elapsed(),
+                    victim: attacker
+                        .current_victim()
+                        .and_then(|victim| entities.iter().position(|e| *e == victim)),
+                    victim_in_range_fraction: attacker.current_victim_in_range_fraction(),
+                }),
+                follow_path: follow_path
+                    .map(|follow_path| (follow_path.get_goal(), follow_path.get_speed())),
+                waterball_elapsed: waterball.map(|waterball| waterball.timer.elapsed()),
+            },
+        )
+        .collect();
+
+    SimSnapshot {
+        elapsed,
+        level,
+        cards,
+        progress,
+        stats,
+        combat_log,
+    }
+}
+
+/// Like spawning from a [`Level`], but cards pick up where they were instead of starting fresh
+fn restore_snapshot(world: &mut World, index: usize) {
+    let Some(snapshot) = world.resource::<Timeline>().snapshots.get(index).cloned() else {
+        return;
+    };
+
+    *world.resource_mut::<CardConsts>() =
+        CardConsts::from_overrides(&snapshot.level.consts_overrides);
+    let _ = world.run_system_once(delete_all::<LevelEntity>);
+    let _ = world.run_system_once(clear_deckbar);
+    world.insert_resource(Terrain::from_tiles(&snapshot.level.terrain));
+    world.insert_resource(PlacementZones(snapshot.level.placement_zones.clone()));
+
+    for card in &snapshot.level.starting_deckbar {
+        PushToDeckbar(*card).apply(world);
+    }
+
+    let entities: Vec<Entity> = snapshot
+        .cards
+        .iter()
+        .map(|card_state| spawn_card(world, card_state.card, card_state.position))
+        .collect();
+    // Lets the spawn observers run first, the farmer's one would overwrite the restored path
+    world.flush();
+
+    for (card_state, &entity) in snapshot.cards.iter().zip(&entities) {
+        let mut entity_mut = world.entity_mut(entity);
+
+        if let (Some(health), Some(mut health_c)) =
+            (card_state.health, entity_mut.get_mut::<Health>())
+        {
+            health_c.current_health = health;
+        }
+
+        if let (Some(attacker), Some(mut attacker_c)) =
+            (&card_state.attacker, entity_mut.get_mut::<Attacker>())
+        {
+            let victim = attacker
+                .victim
+                .map(|victim| (entities[victim], attacker.victim_in_range_fraction));
+            attacker_c.restore(attacker.cooldown_elapsed, victim);
+        }
+
+        if let (Some(elapsed), Some(mut waterball)) = (
+            card_state.waterball_elapsed,
+            entity_mut.get_mut::<Waterball>(),
+        ) {
+            waterball.timer.set_elapsed(elapsed);
+        }
+
+        match card_state.follow_path {
+            Some((goal, speed)) => {
+                entity_mut.insert(FollowPath::new(goal, speed));
+            }
+            None => {
+                entity_mut.remove::<FollowPath>();
+            }
+        }
+    }
+
+    // Respawning the cards sends spawn and path events, none of which really happened
+    world.resource_mut::<CombatLog>().skip_pending_events();
+
+    world.insert_resource(snapshot.progress);
+    world.insert_resource(snapshot.stats);
+    world
+        .resource_mut::<NextState<LevelProgress>>()
+        .set(LevelProgress::Null);
+}
+
+pub fn timeline_ui(ui: &mut Ui, timeline: &mut Timeline, commands: &mut Commands) {
+    if timeline.snapshots.is_empty() {
+        ui.label("Unpause to start recording");
+        return;
+    }
+
+    let last = timeline.snapshots.len() - 1;
+    let mut index = timeline.rewound_to.unwrap_or(last);
+
+    let slider = ui.add(
+        Slider::new(&mut index, 0..=last)
+            .show_value(false)
+            .text(format!("{:.1}s", timeline.snapshots[index].elapsed)),
+    );
+
+    if slider.changed() {
+        timeline.rewound_to = Some(index);
+        commands.queue(Pause);
+        commands.queue(move |world: &mut World| restore_snapshot(world, index));
+    }
+
+    if timeline.rewound_to.is_some() {
+        ui.label("Unpausing continues from here and drops what came after");
+    }
+}
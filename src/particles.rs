@@ -0,0 +1,246 @@
+use bevy::prelude::*;
+use bevy_common_assets::json::JsonAssetPlugin;
+use serde::Deserialize;
+use std::collections::HashMap;
+use DuckSlayer::delete_all;
+
+use crate::{
+    card::{
+        combat_events::{DamageDealt, WaterballExploded},
+        Card, Farmer,
+    },
+    global::GameState,
+};
+
+/// Farmers kick up dust every time they've walked this far
+const DUST_STEP_DISTANCE: f32 = 25.;
+
+/// A burst of particles, loaded from `particles/*.particles.json`. Ranges are `[min, max]` and
+/// each particle picks its own value from them
+#[derive(Asset, TypePath, Deserialize, Debug)]
+pub struct ParticleEmitter {
+    count: u32,
+    lifetime: (f32, f32),
+    speed: (f32, f32),
+    /// Degrees, 0 is to the right
+    #[serde(default)]
+    direction: f32,
+    /// Degrees either side of `direction` particles can go in
+    #[serde(default = "full_spread")]
+    spread: f32,
+    /// Size at the start and end of a particle's life
+    size: (f32, f32),
+    /// Linear RGBA at the start and end of a particle's life
+    start_color: [f32; 4],
+    end_color: [f32; 4],
+    /// Added to the vertical speed every second, negative falls
+    #[serde(default)]
+    gravity: f32,
+    /// Particles are plain squares without one
+    #[serde(default)]
+    image: Option<String>,
+}
+
+fn full_spread() -> f32 {
+    180.
+}
+
+#[derive(Resource)]
+struct Emitters {
+    splash: Handle<ParticleEmitter>,
+    feathers: Handle<ParticleEmitter>,
+    dust: Handle<ParticleEmitter>,
+}
+
+#[derive(Component)]
+#[require(Name::new("Particle"))]
+struct Particle {
+    velocity: Vec2,
+    gravity: f32,
+    age: f32,
+    lifetime: f32,
+    size: (f32, f32),
+    start_color: LinearRgba,
+    end_color: LinearRgba,
+}
+
+/// Xorshift, particles don't need anything better and it saves a dependency
+#[derive(Resource)]
+struct ParticleRng(u32);
+
+impl ParticleRng {
+    fn next_f32(&mut self) -> f32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+        self.0 as f32 / u32::MAX as f32
+    }
+
+    fn range(&mut self, (min, max): (f32, f32)) -> f32 {
+        min + (max - min) * self.next_f32()
+    }
+}
+
+pub fn particles_plugin(app: &mut App) {
+    app.add_plugins(JsonAssetPlugin::<ParticleEmitter>::new(&["particles.json"]))
+        .add_systems(Startup, load_emitters)
+        .add_systems(
+            FixedPostUpdate,
+            (
+                splash_waterballs,
+                feathers_on_quakka_death,
+                dust_behind_farmers,
+            )
+                .run_if(in_state(GameState::InGame)),
+        )
+        .add_systems(Update, update_particles)
+        .add_systems(OnExit(GameState::InGame), delete_all::<Particle>)
+        .insert_resource(ParticleRng(0x9E37_79B9));
+}
+
+fn load_emitters(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(Emitters {
+        splash: asset_server.load("particles/splash.particles.json"),
+        feathers: asset_server.load("particles/feathers.particles.json"),
+        dust: asset_server.load("particles/dust.particles.json"),
+    });
+}
+
+fn emit(
+    emitter: &ParticleEmitter,
+    position: Vec2,
+    rng: &mut ParticleRng,
+    asset_server: &AssetServer,
+    commands: &mut Commands,
+) {
+    let image = emitter
+        .image
+        .as_ref()
+        .map_or(Handle::default(), |image| asset_server.load(image));
+
+    for _ in 0..emitter.count {
+        let angle = (emitter.direction + rng.range((-emitter.spread, emitter.spread))).to_radians();
+        let velocity = Vec2::from_angle(angle) * rng.range(emitter.speed);
+        let start_color = LinearRgba::from_f32_array(emitter.start_color);
+
+        commands.spawn((
+            Particle {
+                velocity,
+                gravity: emitter.gravity,
+                age: 0.,
+                lifetime: rng.range(emitter.lifetime),
+                size: emitter.size,
+                start_color,
+                end_color: LinearRgba::from_f32_array(emitter.end_color),
+            },
+            Sprite {
+                image: image.clone(),
+                color: start_color.into(),
+                custom_size: Some(Vec2::splat(emitter.size.0)),
+                ..default()
+            },
+            // In front of the cards, behind the damage numbers
+            Transform::from_translation(position.extend(5.)),
+        ));
+    }
+}
+
+fn splash_waterballs(
+    mut waterball_exploded_evr: EventReader<WaterballExploded>,
+    emitters: Res<Emitters>,
+    emitter_assets: Res<Assets<ParticleEmitter>>,
+    mut rng: ResMut<ParticleRng>,
+    asset_server: Res<AssetServer>,
+    mut commands: Commands,
+) {
+    let Some(splash) = emitter_assets.get(&emitters.splash) else {
+        return;
+    };
+
+    for waterball_exploded in waterball_exploded_evr.read() {
+        emit(
+            splash,
+            waterball_exploded.position,
+            &mut rng,
+            &asset_server,
+            &mut commands,
+        );
+    }
+}
+
+fn feathers_on_quakka_death(
+    mut damage_dealt_evr: EventReader<DamageDealt>,
+    emitters: Res<Emitters>,
+    emitter_assets: Res<Assets<ParticleEmitter>>,
+    mut rng: ResMut<ParticleRng>,
+    asset_server: Res<AssetServer>,
+    mut commands: Commands,
+) {
+    let Some(feathers) = emitter_assets.get(&emitters.feathers) else {
+        return;
+    };
+
+    for damage_dealt in damage_dealt_evr.read() {
+        if damage_dealt.lethal && damage_dealt.victim_card == Card::Quakka {
+            emit(
+                feathers,
+                damage_dealt.victim_position,
+                &mut rng,
+                &asset_server,
+                &mut commands,
+            );
+        }
+    }
+}
+
+fn dust_behind_farmers(
+    farmers: Query<(Entity, &Transform), With<Farmer>>,
+    mut last_step: Local<HashMap<Entity, Vec2>>,
+    emitters: Res<Emitters>,
+    emitter_assets: Res<Assets<ParticleEmitter>>,
+    mut rng: ResMut<ParticleRng>,
+    asset_server: Res<AssetServer>,
+    mut commands: Commands,
+) {
+    last_step.retain(|farmer, _| farmers.contains(*farmer));
+
+    let Some(dust) = emitter_assets.get(&emitters.dust) else {
+        return;
+    };
+
+    for (farmer, transform) in farmers {
+        let position = transform.translation.truncate();
+        let last = last_step.entry(farmer).or_insert(position);
+
+        if last.distance(position) >= DUST_STEP_DISTANCE {
+            *last = position;
+            emit(dust, position, &mut rng, &asset_server, &mut commands);
+        }
+    }
+}
+
+fn update_particles(
+    particles: Query<(Entity, &mut Particle, &mut Transform, &mut Sprite)>,
+    time: Res<Time>,
+    mut commands: Commands,
+) {
+    let delta = time.delta_secs();
+
+    for (entity, mut particle, mut transform, mut sprite) in particles {
+        particle.age += delta;
+        if particle.age >= particle.lifetime {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        let gravity = particle.gravity;
+        particle.velocity.y += gravity * delta;
+        transform.translation += (particle.velocity * delta).extend(0.);
+
+        let t = particle.age / particle.lifetime;
+        sprite.color = particle.start_color.mix(&particle.end_color, t).into();
+        sprite.custom_size = Some(Vec2::splat(
+            particle.size.0 + (particle.size.1 - particle.size.0) * t,
+        ));
+    }
+}
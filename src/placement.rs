@@ -0,0 +1,169 @@
+use bevy::{
+    color::palettes::tailwind::*, ecs::system::SystemParam, prelude::*, ui::RelativeCursorPosition,
+};
+use bevy_egui::input::EguiWantsInput;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    card::{Card, CardConsts, Health, MaybeCard, WaterballTarget},
+    deckbar::{DeckBarRoot, DraggedCard, SelectedCard},
+    global::{CursorWorldCoords, GameState, InEditorRes, IsPointerOverUi},
+    manage_level::IsPaused,
+    terrain::Terrain,
+};
+
+/// Colour of the placement preview, the range circle and the hover sprite, when the card can't go
+/// where the cursor is
+pub const INVALID_PLACEMENT_COLOR: Srgba = RED_500;
+/// Outline of the zones the selected card can be placed in
+pub const PLACEMENT_ZONE_COLOR: Srgba = GREEN_400;
+
+/// A rect in the arena that `card` can be placed in, as it's stored in a level file
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct PlacementZone {
+    pub card: Card,
+    pub rect: Rect,
+}
+
+/// Where each card can be placed. A card with no zones of its own can go anywhere the terrain
+/// allows, once it has one it can only go inside its zones
+#[derive(Resource, Default, Clone, Debug, Deref, DerefMut)]
+pub struct PlacementZones(pub Vec<PlacementZone>);
+
+impl PlacementZones {
+    pub fn for_card(&self, card: Card) -> impl Iterator<Item = &PlacementZone> {
+        self.0.iter().filter(move |zone| zone.card == card)
+    }
+
+    pub fn allows(&self, card: Card, pos: Vec2) -> bool {
+        let mut zones = self.for_card(card).peekable();
+        zones.peek().is_none() || zones.any(|zone| zone.rect.contains(pos))
+    }
+}
+
+/// Whether a card can be placed at the cursor. Shared by placing cards and previewing them, so the
+/// preview never disagrees with what a click does
+#[derive(SystemParam)]
+pub struct Placement<'w, 's> {
+    terrain: Res<'w, Terrain>,
+    is_pointer_over_ui: Res<'w, IsPointerOverUi>,
+    egui_wants_input: Res<'w, EguiWantsInput>,
+    ui_nodes: Query<'w, 's, (Entity, &'static Interaction), With<Node>>,
+    cursor_world_coords: Res<'w, CursorWorldCoords>,
+    zones: Res<'w, PlacementZones>,
+    in_editor: Res<'w, InEditorRes>,
+    is_paused: Res<'w, State<IsPaused>>,
+    dragged_card: Option<Res<'w, DraggedCard>>,
+    deckbar: Single<'w, &'static RelativeCursorPosition, With<DeckBarRoot>>,
+}
+
+impl Placement<'_, '_> {
+    pub fn cursor(&self) -> Vec2 {
+        **self.cursor_world_coords
+    }
+
+    /// A dragged card's deckbar slot stays pressed until it's dropped, which would count as being
+    /// over the UI the whole way, so that slot is left out while dragging
+    pub fn is_pointer_over_ui(&self) -> bool {
+        let Some(dragged_card) = &self.dragged_card else {
+            return **self.is_pointer_over_ui;
+        };
+
+        self.deckbar.mouse_over()
+            || self.egui_wants_input.wants_any_pointer_input()
+            || self
+                .ui_nodes
+                .iter()
+                .any(|(e, i)| e != dragged_card.slot && *i != Interaction::None)
+    }
+
+    /// Zones only limit the player. While the editor is paused the level is being laid out and
+    /// they'd get in the way, once it's running it's being played like any other level
+    pub fn can_place(&self, card: Card) -> bool {
+        let laying_out_level = **self.in_editor && **self.is_paused == IsPaused::True;
+
+        !self.is_pointer_over_ui()
+            && self.terrain.is_placeable(card, self.cursor())
+            && (laying_out_level || self.zones.allows(card, self.cursor()))
+    }
+}
+
+pub fn placement_plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        preview_placement.run_if(in_state(GameState::InGame)),
+    )
+    .add_systems(OnExit(GameState::InGame), clear_placement_zones)
+    .init_resource::<PlacementZones>();
+}
+
+pub fn outline_rect(draw: &mut Gizmos, rect: Rect, color: impl Into<Color>) {
+    draw.rect_2d(
+        Isometry2d::from_translation(rect.center()),
+        rect.size(),
+        color,
+    );
+}
+
+/// How far a card reaches once it's placed, its attack range or its blast radius
+fn reach(card: Card, card_consts: &CardConsts) -> Option<f32> {
+    match card {
+        Card::Quakka => Some(card_consts.quakka.range),
+        Card::Nest => Some(card_consts.nest.range),
+        Card::Waterball => Some(card_consts.waterball.radius),
+        Card::Farmer => None,
+    }
+}
+
+fn preview_placement(
+    selected_card: Option<Single<&MaybeCard, With<SelectedCard>>>,
+    placement: Placement,
+    units: Query<(&Transform, Has<WaterballTarget>), With<Health>>,
+    card_consts: Res<CardConsts>,
+    mut draw: Gizmos,
+) {
+    let Some(card) = selected_card.and_then(|selected_card| selected_card.0) else {
+        return;
+    };
+    for zone in placement.zones.for_card(card) {
+        outline_rect(&mut draw, zone.rect, PLACEMENT_ZONE_COLOR);
+    }
+
+    if placement.is_pointer_over_ui() {
+        return;
+    }
+
+    let Some(reach) = reach(card, &card_consts) else {
+        return;
+    };
+
+    let color = if placement.can_place(card) {
+        Color::WHITE
+    } else {
+        INVALID_PLACEMENT_COLOR.into()
+    };
+    draw.circle_2d(
+        Isometry2d::from_translation(placement.cursor()),
+        reach,
+        color,
+    );
+
+    if card != Card::Waterball {
+        return;
+    }
+
+    // Rings everything the blast would reach, red for what it'd actually hurt
+    for (transform, is_target) in units {
+        let position = transform.translation.truncate();
+        if position.distance(placement.cursor()) >= reach {
+            continue;
+        }
+
+        let color = if is_target { RED_400 } else { GRAY_300 };
+        draw.circle_2d(Isometry2d::from_translation(position), 40., color);
+    }
+}
+
+fn clear_placement_zones(mut zones: ResMut<PlacementZones>) {
+    *zones = PlacementZones::default();
+}
@@ -0,0 +1,134 @@
+use bevy::{color::palettes::tailwind::*, prelude::*};
+use DuckSlayer::delete_all;
+
+use crate::{
+    back_btn::PreviousScreen,
+    global::{not_in_editor, GameState},
+    level_select::{level_exists, CurrentLevelNumber, PlaySelectableLevel},
+    manage_level::{LevelMemory, LevelProgress, LevelStats, RestartLevel},
+};
+
+#[derive(Component)]
+#[require(Name::new("Results Screen"))]
+struct ResultsScreen;
+
+pub fn results_screen_plugin(app: &mut App) {
+    app.add_systems(
+        OnEnter(LevelProgress::GameWon),
+        spawn_results_screen.run_if(not_in_editor),
+    )
+    .add_systems(
+        OnEnter(LevelProgress::GameOver),
+        spawn_results_screen.run_if(not_in_editor),
+    )
+    .add_systems(OnExit(LevelProgress::GameWon), delete_all::<ResultsScreen>)
+    .add_systems(OnExit(LevelProgress::GameOver), delete_all::<ResultsScreen>)
+    .add_systems(OnExit(GameState::InGame), delete_all::<ResultsScreen>);
+}
+
+fn spawn_results_screen(
+    level_progress: Res<State<LevelProgress>>,
+    level_stats: Res<LevelStats>,
+    level: Res<LevelMemory>,
+    current_level_number: Res<CurrentLevelNumber>,
+    mut commands: Commands,
+) {
+    let won = **level_progress == LevelProgress::GameWon;
+    let title = if won { "You won! :)" } else { "You lost :(" };
+
+    let mut lines = vec![
+        format!("Time: {:.1}s", level_stats.elapsed),
+        format!("Cards placed: {}", level_stats.total_cards_placed()),
+    ];
+
+    for (card, damage) in &level_stats.damage_dealt {
+        lines.push(format!("{} damage: {damage:.0}", card.to_string()));
+    }
+
+    // Only the card the player has to protect counts as theirs, the rest are enemies
+    let lose_card = level.lose_condition.card;
+    lines.push(format!(
+        "{} lost: {}",
+        lose_card.to_string(),
+        level_stats.deaths_of(lose_card)
+    ));
+
+    if let Some(par) = level.metadata.par {
+        let beat_par = won
+            && level_stats.total_cards_placed() <= par.cards_used
+            && level_stats.elapsed <= par.time_secs;
+        let verdict = if beat_par { "beaten!" } else { "not beaten" };
+        lines.push(format!("Par ({}): {verdict}", par.to_string()));
+    }
+
+    let next_level = current_level_number
+        .map(|i| i + 1)
+        .filter(|i| won && level_exists(*i));
+
+    commands
+        .spawn((
+            ResultsScreen,
+            Node {
+                position_type: PositionType::Absolute,
+                margin: UiRect::AUTO,
+                padding: UiRect::all(Val::Px(30.)),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(8.),
+                ..default()
+            },
+            BackgroundColor(CYAN_500.into()),
+            BorderRadius::all(Val::Px(25.)),
+            GlobalZIndex(1),
+        ))
+        .with_children(|p| {
+            p.spawn((Text::new(title), TextFont::from_font_size(36.)));
+
+            for line in lines {
+                p.spawn(Text::new(line));
+            }
+
+            p.spawn(Node {
+                column_gap: Val::Px(15.),
+                margin: UiRect::top(Val::Px(15.)),
+                ..default()
+            })
+            .with_children(|p| {
+                results_btn(p, "Retry").observe(
+                    |_: Trigger<Pointer<Click>>, mut commands: Commands| {
+                        commands.queue(RestartLevel);
+                    },
+                );
+
+                if let Some(next_level) = next_level {
+                    results_btn(p, "Next level").observe(
+                        move |_: Trigger<Pointer<Click>>, mut commands: Commands| {
+                            commands.queue(PlaySelectableLevel(next_level));
+                        },
+                    );
+                }
+
+                results_btn(p, "Back").observe(
+                    |_: Trigger<Pointer<Click>>,
+                     mut game_state: ResMut<NextState<GameState>>,
+                     mut previous_screen: ResMut<PreviousScreen>| {
+                        game_state.set(previous_screen.unwrap_or(GameState::TitleScreen));
+                        **previous_screen = None;
+                    },
+                );
+            });
+        });
+}
+
+fn results_btn<'a>(p: &'a mut ChildSpawnerCommands, text: &str) -> EntityCommands<'a> {
+    p.spawn((
+        Button,
+        Node {
+            padding: UiRect::axes(Val::Px(15.), Val::Px(8.)),
+            ..default()
+        },
+        BackgroundColor(YELLOW_400.into()),
+        BorderRadius::all(Val::Px(10.)),
+        children![(Text::new(text), TextColor(Color::BLACK))],
+    ))
+}
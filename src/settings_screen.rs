@@ -1,3 +1,4 @@
+use crate::hit_feedback::ShowHitFeedback;
 use crate::volume_settings::VolumeSettings;
 use crate::widgets::checkbox::create_checkbox;
 use crate::widgets::checkbox::Toggled;
@@ -23,6 +24,7 @@ fn spawn_settings_screen(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     volume: Res<VolumeSettings>,
+    show_hit_feedback: Res<ShowHitFeedback>,
 ) {
     let settings_screen = commands
         .spawn((
@@ -38,7 +40,7 @@ fn spawn_settings_screen(
                 align_items: AlignItems::Center,
                 justify_items: JustifyItems::Center,
 
-                grid_template_rows: RepeatedGridTrack::auto(3),
+                grid_template_rows: RepeatedGridTrack::auto(4),
                 grid_template_columns: RepeatedGridTrack::auto(3),
 
                 ..default()
@@ -46,7 +48,8 @@ fn spawn_settings_screen(
             BackgroundColor(CYAN_500.into()),
             BorderRadius::all(Val::Percent(10.)),
             BorderColor(CYAN_800.into()),
-            ZIndex(1),
+            // Above the pause menu, which it can be opened from
+            GlobalZIndex(2),
         ))
         .with_children(|p| {
             // Bar holding the X, had to do some hackery to get the X in the top right
@@ -106,6 +109,20 @@ fn spawn_settings_screen(
             node.grid_column = GridPlacement::start(2);
         });
 
+    let hit_feedback_checkbox = create_checkbox(&mut commands, **show_hit_feedback);
+    commands
+        .entity(hit_feedback_checkbox)
+        .observe(
+            |toggled: Trigger<Toggled>, mut show_hit_feedback: ResMut<ShowHitFeedback>| {
+                **show_hit_feedback = toggled.is_checked;
+            },
+        )
+        .entry::<Node>()
+        .and_modify(|mut node| {
+            node.grid_row = GridPlacement::start(4);
+            node.grid_column = GridPlacement::start(2);
+        });
+
     let sfx_slider = create_slider(&mut commands, volume.get_sfx().to_linear() * 100.);
     commands
         .entity(sfx_slider)
@@ -138,6 +155,7 @@ fn spawn_settings_screen(
         .entity(settings_screen)
         .add_child(mute_sfx_checkbox)
         .add_child(mute_music_checkbox)
+        .add_child(hit_feedback_checkbox)
         .add_child(sfx_slider)
         .add_child(music_slider)
         .with_children(|p| {
@@ -161,6 +179,7 @@ fn spawn_settings_screen(
             p.spawn(create_text("Volume", 1, 3));
             p.spawn(create_text("SFX", 2, 1));
             p.spawn(create_text("Music", 3, 1));
+            p.spawn(create_text("Hit numbers", 4, 1));
         });
 }
 
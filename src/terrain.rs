@@ -0,0 +1,179 @@
+use bevy::{
+    color::palettes::tailwind::{AMBER_700, SKY_500, STONE_600},
+    platform::collections::HashMap,
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+use DuckSlayer::delete_all;
+
+use crate::{
+    card::Card,
+    global::{
+        get_entire_map_rect, get_left_river_rect, get_middle_river_rect, get_right_river_rect,
+        GameState,
+    },
+};
+
+/// Width and height of a single paintable terrain cell
+pub const TERRAIN_CELL_SIZE: f32 = 30.;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TerrainKind {
+    Water,
+    Bridge,
+    Wall,
+}
+
+impl TerrainKind {
+    pub fn to_string(&self) -> String {
+        match self {
+            TerrainKind::Water => "Water".to_string(),
+            TerrainKind::Bridge => "Bridge".to_string(),
+            TerrainKind::Wall => "Wall".to_string(),
+        }
+    }
+
+    fn color(&self) -> Color {
+        match self {
+            TerrainKind::Water => SKY_500.with_alpha(0.6).into(),
+            TerrainKind::Bridge => AMBER_700.with_alpha(0.8).into(),
+            TerrainKind::Wall => STONE_600.with_alpha(0.9).into(),
+        }
+    }
+}
+
+/// A painted cell as it's stored in a level file
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct TerrainTile {
+    pub cell: (i32, i32),
+    pub kind: TerrainKind,
+}
+
+/// Terrain painted over the arena. Cells that haven't been painted fall back to the rivers baked
+/// into the arena background
+#[derive(Resource, Default, Clone, Debug)]
+pub struct Terrain(HashMap<(i32, i32), TerrainKind>);
+
+#[derive(Component)]
+#[require(Name::new("Terrain Tile Sprite"))]
+struct TerrainTileSprite;
+
+pub fn terrain_plugin(app: &mut App) {
+    app.add_systems(
+        FixedUpdate,
+        redraw_terrain
+            .run_if(in_state(GameState::InGame).and(resource_exists_and_changed::<Terrain>)),
+    )
+    .add_systems(
+        OnExit(GameState::InGame),
+        (delete_all::<TerrainTileSprite>, clear_terrain),
+    )
+    .init_resource::<Terrain>();
+}
+
+impl Terrain {
+    pub fn from_tiles(tiles: &[TerrainTile]) -> Terrain {
+        Terrain(tiles.iter().map(|tile| (tile.cell, tile.kind)).collect())
+    }
+
+    pub fn to_tiles(&self) -> Vec<TerrainTile> {
+        let mut tiles: Vec<TerrainTile> = self
+            .0
+            .iter()
+            .map(|(cell, kind)| TerrainTile {
+                cell: *cell,
+                kind: *kind,
+            })
+            .collect();
+
+        // HashMap order is random, sorting keeps saved levels diffable
+        tiles.sort_by_key(|tile| tile.cell);
+        tiles
+    }
+
+    pub fn cell_at(pos: Vec2) -> (i32, i32) {
+        let cell = (pos / TERRAIN_CELL_SIZE).floor();
+        (cell.x as i32, cell.y as i32)
+    }
+
+    pub fn cell_rect(cell: (i32, i32)) -> Rect {
+        let min = Vec2::new(cell.0 as f32, cell.1 as f32) * TERRAIN_CELL_SIZE;
+        Rect::from_corners(min, min + Vec2::splat(TERRAIN_CELL_SIZE))
+    }
+
+    pub fn paint(&mut self, pos: Vec2, kind: Option<TerrainKind>) {
+        let cell = Terrain::cell_at(pos);
+        match kind {
+            Some(kind) => self.0.insert(cell, kind),
+            None => self.0.remove(&cell),
+        };
+    }
+
+    pub fn is_painted(&self, pos: Vec2, kind: Option<TerrainKind>) -> bool {
+        self.0.get(&Terrain::cell_at(pos)).copied() == kind
+    }
+
+    pub fn get(&self, pos: Vec2) -> Option<TerrainKind> {
+        if let Some(kind) = self.0.get(&Terrain::cell_at(pos)) {
+            return Some(*kind);
+        }
+
+        let in_river = get_left_river_rect().contains(pos)
+            || get_middle_river_rect().contains(pos)
+            || get_right_river_rect().contains(pos);
+
+        if in_river {
+            Some(TerrainKind::Water)
+        } else {
+            None
+        }
+    }
+
+    pub fn is_walkable(&self, pos: Vec2) -> bool {
+        get_entire_map_rect().contains(pos)
+            && match self.get(pos) {
+                Some(TerrainKind::Water) | Some(TerrainKind::Wall) => false,
+                Some(TerrainKind::Bridge) | None => true,
+            }
+    }
+
+    pub fn is_placeable(&self, card: Card, pos: Vec2) -> bool {
+        match self.get(pos) {
+            Some(TerrainKind::Water) => card.is_placeable_over_water(),
+            Some(TerrainKind::Wall) => card.is_placeable_over_walls(),
+            Some(TerrainKind::Bridge) | None => true,
+        }
+    }
+}
+
+fn redraw_terrain(
+    terrain: Res<Terrain>,
+    old_tiles: Query<Entity, With<TerrainTileSprite>>,
+    mut commands: Commands,
+) {
+    for old_tile in old_tiles {
+        commands.entity(old_tile).despawn();
+    }
+
+    for (cell, kind) in &terrain.0 {
+        let rect = Terrain::cell_rect(*cell);
+        commands.spawn((
+            TerrainTileSprite,
+            Sprite {
+                color: kind.color(),
+                custom_size: Some(rect.size()),
+                ..default()
+            },
+            Transform {
+                // Right above the arena background so cards are drawn over it
+                translation: rect.center().extend(-0.4),
+                ..default()
+            },
+            Pickable::IGNORE,
+        ));
+    }
+}
+
+fn clear_terrain(mut terrain: ResMut<Terrain>) {
+    *terrain = Terrain::default();
+}
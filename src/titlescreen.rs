@@ -1,10 +1,12 @@
 use crate::settings_screen::ShowSettingsScreen;
 use bevy::prelude::*;
+use bevy_egui::{egui, EguiContextPass, EguiContexts};
 use DuckSlayer::delete_all;
 
 use crate::{
     global::*,
-    manage_level::{EnterLevel, Level},
+    load_error::ShowLoadError,
+    manage_level::{decode_share_code, EnterLevel, Level},
 };
 
 #[derive(Component)]
@@ -23,6 +25,10 @@ struct EditorBtn;
 #[require(Button, Name::new("Settings Btn"))]
 struct SettingsBtn;
 
+/// What's been typed into the level code box so far
+#[derive(Resource, Default)]
+struct PastedLevelCode(String);
+
 pub fn titlescreen(app: &mut App) {
     app.add_systems(OnEnter(GameState::TitleScreen), spawn_titlescreen)
         .add_systems(
@@ -34,7 +40,12 @@ pub fn titlescreen(app: &mut App) {
             )
                 .run_if(in_state(GameState::TitleScreen)),
         )
-        .add_systems(OnExit(GameState::TitleScreen), delete_all::<TitleScreen>);
+        .add_systems(
+            EguiContextPass,
+            paste_level_code_window.run_if(in_state(GameState::TitleScreen)),
+        )
+        .add_systems(OnExit(GameState::TitleScreen), delete_all::<TitleScreen>)
+        .init_resource::<PastedLevelCode>();
 }
 
 fn spawn_titlescreen(
@@ -114,6 +125,37 @@ fn start_editor_on_click(
     }
 }
 
+fn paste_level_code_window(
+    mut contexts: EguiContexts,
+    mut pasted_level_code: ResMut<PastedLevelCode>,
+    mut in_editor: ResMut<InEditorRes>,
+    mut commands: Commands,
+) {
+    egui::Window::new("Play a shared level")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::LEFT_BOTTOM, (20., -20.))
+        .show(contexts.ctx_mut(), |ui| {
+            ui.label("Paste a level code");
+            ui.text_edit_singleline(&mut pasted_level_code.0);
+
+            let has_code = !pasted_level_code.0.trim().is_empty();
+            if ui
+                .add_enabled(has_code, egui::Button::new("Play"))
+                .clicked()
+            {
+                match decode_share_code(&pasted_level_code.0) {
+                    Ok(level) => {
+                        **in_editor = false;
+                        pasted_level_code.0.clear();
+                        commands.queue(EnterLevel(level));
+                    }
+                    Err(err) => commands.queue(ShowLoadError(err)),
+                }
+            }
+        });
+}
+
 fn show_settings_on_click(
     interactions: Query<&Interaction, (Changed<Interaction>, With<SettingsBtn>)>,
     mut commands: Commands,
@@ -0,0 +1,40 @@
+use bevy::{prelude::*, transform::TransformSystem};
+
+/// Anything above this is drawn furthest back
+const TOP: f32 = 600.;
+const Z_PER_PIXEL: f32 = 0.0005;
+/// Above the terrain and arena background, which are at negative z
+const BASE_Z: f32 = 1.;
+
+/// Sets z from y every frame, so lower sprites are drawn in front of higher ones. The offset is
+/// added on top, for things that should always be drawn over or under units
+#[derive(Component, Clone, Copy, Default)]
+pub struct YSort(pub f32);
+
+impl YSort {
+    /// In the air, over every unit
+    pub const FLYING: YSort = YSort(1.);
+    /// Flat on the ground, under every unit
+    pub const GROUND: YSort = YSort(-1.);
+}
+
+/// Children are sorted with their parent, this keeps them just in front of it
+pub const CHILD_Z: f32 = 0.0001;
+
+pub fn y_sort_plugin(app: &mut App) {
+    app.add_systems(
+        PostUpdate,
+        y_sort.before(TransformSystem::TransformPropagate),
+    );
+}
+
+fn y_sort(sorted: Query<(&mut Transform, &YSort)>) {
+    for (mut transform, y_sort) in sorted {
+        let depth = ((TOP - transform.translation.y) * Z_PER_PIXEL).clamp(0., 1.);
+        let z = BASE_Z + depth + y_sort.0;
+
+        if transform.translation.z != z {
+            transform.translation.z = z;
+        }
+    }
+}